@@ -0,0 +1,93 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Value;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes `bytes` as padded base64 with the standard alphabet
+fn encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let n = group.iter().enumerate().fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= group.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Decodes padded standard base64, or `None` if `text` isn't valid base64
+fn decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(text.len() / 4 * 3);
+    for (i, group) in text.chunks(4).enumerate() {
+        // padding may only end the last group, taking up its last one or two characters
+        let padding = group.iter().rev().take_while(|&&byte| byte == b'=').count();
+        if padding > 2 || (padding > 0 && i + 1 < text.len() / 4) {
+            return None;
+        }
+
+        let mut n = 0u32;
+        for (j, &byte) in group[..4 - padding].iter().enumerate() {
+            let digit = ALPHABET.iter().position(|&c| c == byte)? as u32;
+            n |= digit << (18 - 6 * j);
+        }
+        output.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+    }
+    Some(output)
+}
+
+impl Value {
+    /// Decodes a string holding standard, padded base64 into its bytes. Returns `None` for
+    /// non-strings and invalid base64.
+    pub fn as_base64_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Value::String(string) => decode(string),
+            _ => None,
+        }
+    }
+
+    /// A string holding `bytes` encoded as standard, padded base64
+    pub fn from_base64_bytes(bytes: &[u8]) -> Value {
+        Value::String(encode(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_round_trip() {
+        assert_eq!(Value::from_base64_bytes(b""), Value::String("".into()));
+        assert_eq!(Value::from_base64_bytes(b"f"), Value::String("Zg==".into()));
+        assert_eq!(Value::from_base64_bytes(b"fo"), Value::String("Zm8=".into()));
+        assert_eq!(Value::from_base64_bytes(b"foo"), Value::String("Zm9v".into()));
+        assert_eq!(Value::from_base64_bytes(b"foobar"), Value::String("Zm9vYmFy".into()));
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..bytes.len() {
+            let value = Value::from_base64_bytes(&bytes[..len]);
+            assert_eq!(value.as_base64_bytes().as_deref(), Some(&bytes[..len]));
+        }
+        assert_eq!(Value::String("+/8=".into()).as_base64_bytes(), Some(vec![0xFB, 0xFF]));
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        for text in ["Zg", "Zg=", "Z===", "Zg==Zg==", "Zm9v!A==", "Zm 9v", "===="] {
+            assert_eq!(Value::String(text.into()).as_base64_bytes(), None, "{}", text);
+        }
+        assert_eq!(Value::Null.as_base64_bytes(), None);
+        assert_eq!(Value::Array(vec![]).as_base64_bytes(), None);
+    }
+}
@@ -0,0 +1,132 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::value::ValueKind;
+use crate::Value;
+
+/// Why a [`Value`] couldn't be converted by [`TryFromValue`]
+#[derive(Debug, PartialEq)]
+pub enum FromValueError {
+    /// The value isn't of a kind the type converts from, or is out of the type's range
+    WrongType { expected: &'static str, found: ValueKind },
+    /// The value at `key` of an object failed to convert
+    InKey { key: String, cause: Box<FromValueError> },
+}
+
+/// Types that can be taken out of a [`Value`] of the matching kind
+pub trait TryFromValue: Sized {
+    fn try_from_value(value: Value) -> Result<Self, FromValueError>;
+}
+
+fn wrong_type<T>(expected: &'static str, value: &Value) -> Result<T, FromValueError> {
+    Err(FromValueError::WrongType {
+        expected,
+        found: value.kind(),
+    })
+}
+
+impl TryFromValue for Value {
+    fn try_from_value(value: Value) -> Result<Self, FromValueError> {
+        Ok(value)
+    }
+}
+
+impl TryFromValue for bool {
+    fn try_from_value(value: Value) -> Result<Self, FromValueError> {
+        match value {
+            Value::Boolean(boolean) => Ok(boolean),
+            value => wrong_type("a boolean", &value),
+        }
+    }
+}
+
+/// Any number, rounded to the closest `f64` if it's stored another way
+impl TryFromValue for f64 {
+    fn try_from_value(value: Value) -> Result<Self, FromValueError> {
+        match value.as_number_lossy() {
+            Some(number) => Ok(number),
+            None => wrong_type("a number", &value),
+        }
+    }
+}
+
+/// An integer, or a number with no fractional part, that fits in `i64`
+impl TryFromValue for i64 {
+    fn try_from_value(value: Value) -> Result<Self, FromValueError> {
+        match value.as_i128().and_then(|integer| i64::try_from(integer).ok()) {
+            Some(integer) => Ok(integer),
+            None => wrong_type("an i64", &value),
+        }
+    }
+}
+
+impl TryFromValue for String {
+    fn try_from_value(value: Value) -> Result<Self, FromValueError> {
+        match value {
+            Value::String(string) => Ok(string),
+            value => wrong_type("a string", &value),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Value {
+    /// Converts every value of an object to `T`, failing with [`FromValueError::InKey`] for
+    /// the first key, in map order, whose value doesn't convert. Anything but an object is a
+    /// [`FromValueError::WrongType`].
+    pub fn try_into_map<T: TryFromValue>(self) -> Result<HashMap<String, T>, FromValueError> {
+        let Value::Object(map) = self else {
+            return wrong_type("an object", &self);
+        };
+        map.into_iter()
+            .map(|(key, value)| match T::try_from_value(value) {
+                Ok(value) => Ok((String::from(&*key), value)),
+                Err(cause) => Err(FromValueError::InKey {
+                    key: String::from(&*key),
+                    cause: Box::new(cause),
+                }),
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_try_into_map() {
+        let map = parse(r#"{"a": 1, "b": 2, "c": -3e2}"#).unwrap().try_into_map::<i64>().unwrap();
+        assert_eq!(map, HashMap::from([("a".into(), 1), ("b".into(), 2), ("c".into(), -300)]));
+
+        let names = parse(r#"{"x": "one", "y": ""}"#).unwrap().try_into_map::<String>().unwrap();
+        assert_eq!(names["x"], "one");
+        assert_eq!(parse("{}").unwrap().try_into_map::<bool>(), Ok(HashMap::new()));
+    }
+
+    #[test]
+    fn test_try_into_map_errors() {
+        let mixed = parse(r#"{"a": 1, "b": "2", "c": 3}"#).unwrap();
+        let err = FromValueError::InKey {
+            key: "b".into(),
+            cause: Box::new(FromValueError::WrongType {
+                expected: "an i64",
+                found: ValueKind::String,
+            }),
+        };
+        assert_eq!(mixed.try_into_map::<i64>(), Err(err));
+
+        let fraction = parse(r#"{"a": 1.5}"#).unwrap();
+        assert!(matches!(fraction.try_into_map::<i64>(), Err(FromValueError::InKey { key, .. }) if key == "a"));
+        assert_eq!(
+            parse("[1]").unwrap().try_into_map::<f64>(),
+            Err(FromValueError::WrongType {
+                expected: "an object",
+                found: ValueKind::Array
+            })
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use alloc::string::ToString;
+use core::fmt;
+
+use crate::Value;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A point in time in UTC to the nanosecond, read from and written as RFC 3339 text like
+/// `2024-02-29T12:30:00.5Z`. Only the four-digit years RFC 3339 can write are covered.
+///
+/// Only available with the `chrono` feature, where it stands in for `chrono::DateTime<Utc>`
+/// and is meant to be replaced by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// Seconds since 1970-01-01T00:00:00Z, leap seconds not counted
+    seconds: i64,
+    nanosecond: u32,
+}
+
+impl DateTime {
+    /// The time `seconds` and `nanosecond` after the Unix epoch. Returns `None` when
+    /// `nanosecond` isn't below one second or the year isn't between 0 and 9999.
+    pub fn from_unix(seconds: i64, nanosecond: u32) -> Option<DateTime> {
+        let min = days_from_civil(0, 1, 1) * SECONDS_PER_DAY;
+        let max = days_from_civil(10_000, 1, 1) * SECONDS_PER_DAY;
+        ((min..max).contains(&seconds) && nanosecond < 1_000_000_000).then_some(DateTime { seconds, nanosecond })
+    }
+
+    pub fn unix_seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+}
+
+/// Writes RFC 3339 text in UTC, with as many fractional digits as the nanoseconds need
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.seconds.div_euclid(SECONDS_PER_DAY));
+        let time = self.seconds.rem_euclid(SECONDS_PER_DAY);
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60)?;
+        if self.nanosecond > 0 {
+            let (mut fraction, mut digits) = (self.nanosecond, 9);
+            while fraction % 10 == 0 {
+                fraction /= 10;
+                digits -= 1;
+            }
+            write!(f, ".{:0width$}", fraction, width = digits)?;
+        }
+        f.write_str("Z")
+    }
+}
+
+/// Days from 1970-01-01 to the given date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // counted in 400 year eras of years starting in March, so the leap day ends each year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * i64::from((month + 9) % 12) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date `days` after 1970-01-01, the inverse of [`days_from_civil`]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u32;
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Reads an RFC 3339 timestamp, ex. `2024-02-29T12:30:00.123+01:00`, converting it to UTC.
+/// Fractions finer than nanoseconds are truncated, and a leap second, `:60`, reads as the
+/// first second of the next minute.
+fn parse_rfc3339(text: &str) -> Option<DateTime> {
+    let bytes = text.as_bytes();
+    let number = |start: usize, end: usize| {
+        let digits = bytes.get(start..end)?;
+        digits.iter().try_fold(0u32, |n, byte| byte.is_ascii_digit().then(|| n * 10 + u32::from(byte - b'0')))
+    };
+    let separator = |index: usize, allowed: &[u8]| bytes.get(index).is_some_and(|byte| allowed.contains(byte));
+
+    let separated = [(4, b"-" as &[u8]), (7, b"-"), (10, b"Tt "), (13, b":"), (16, b":")];
+    if !separated.iter().all(|&(index, allowed)| separator(index, allowed)) {
+        return None;
+    }
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut index = 19;
+    let mut nanosecond = 0;
+    if separator(index, b".") {
+        let digits = bytes[index + 1..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        for (i, digit) in bytes[index + 1..index + 1 + digits].iter().take(9).enumerate() {
+            nanosecond += u32::from(digit - b'0') * 10u32.pow(8 - i as u32);
+        }
+        index += 1 + digits;
+    }
+
+    let offset = match &bytes[index..] {
+        b"Z" | b"z" => 0,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let (hours, minutes) = (number(index + 1, index + 3)?, number(index + 4, index + 6)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = i64::from(hours * 3600 + minutes * 60);
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+
+    let days = days_from_civil(i64::from(year), month, day);
+    let seconds = days * SECONDS_PER_DAY + i64::from(hour * 3600 + minute * 60 + second) - offset;
+    DateTime::from_unix(seconds, nanosecond)
+}
+
+impl Value {
+    /// Reads a string holding an RFC 3339 timestamp, ex. `"2024-02-29T12:30:00+01:00"`, as a
+    /// UTC [`DateTime`]. Returns `None` for non-strings and strings that aren't a timestamp.
+    pub fn as_datetime(&self) -> Option<DateTime> {
+        match self {
+            Value::String(string) => parse_rfc3339(string),
+            _ => None,
+        }
+    }
+
+    /// A string holding `datetime` as RFC 3339 text in UTC, ex. `"2024-02-29T11:30:00Z"`
+    pub fn from_datetime(datetime: DateTime) -> Value {
+        Value::String(datetime.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(text: &str) -> Option<DateTime> {
+        Value::String(text.into()).as_datetime()
+    }
+
+    #[test]
+    fn test_as_datetime() {
+        let epoch = DateTime::from_unix(0, 0).unwrap();
+        assert_eq!(datetime("1970-01-01T00:00:00Z"), Some(epoch));
+        assert_eq!(datetime("1970-01-01t01:00:00+01:00"), Some(epoch));
+        assert_eq!(datetime("1969-12-31 23:30:00.000-00:30"), Some(epoch));
+
+        let leap_day = datetime("2024-02-29T12:30:05.25+01:00").unwrap();
+        assert_eq!((leap_day.unix_seconds(), leap_day.nanosecond()), (1_709_206_205, 250_000_000));
+        assert_eq!(datetime("2016-12-31T23:59:60Z"), DateTime::from_unix(1_483_228_800, 0));
+        assert_eq!(datetime("2000-01-01T00:00:00.1234567891Z").unwrap().nanosecond(), 123_456_789);
+        assert_eq!(datetime("0000-01-01T00:00:00Z").unwrap().unix_seconds(), -62_167_219_200);
+    }
+
+    #[test]
+    fn test_as_datetime_malformed() {
+        for text in [
+            "",
+            "2024-02-29",
+            "2023-02-29T00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-01-01T24:00:00Z",
+            "2024-01-01T00:00:00",
+            "2024-01-01T00:00:00.Z",
+            "2024-01-01T00:00:00+0100",
+            "2024-01-01T00:00:00Z ",
+            "2024-1-01T00:00:00Z",
+            "0000-01-01T00:00:00+00:01",
+        ] {
+            assert_eq!(datetime(text), None, "{}", text);
+        }
+        assert_eq!(Value::Number(0.0).as_datetime(), None);
+    }
+
+    #[test]
+    fn test_from_datetime() {
+        let value = Value::from_datetime(DateTime::from_unix(1_709_206_205, 250_000_000).unwrap());
+        assert_eq!(value, Value::String("2024-02-29T11:30:05.25Z".into()));
+        assert_eq!(value.as_datetime(), DateTime::from_unix(1_709_206_205, 250_000_000));
+
+        let max = DateTime::from_unix(253_402_300_799, 999_999_999).unwrap();
+        assert_eq!(Value::from_datetime(max), Value::String("9999-12-31T23:59:59.999999999Z".into()));
+        assert_eq!(DateTime::from_unix(253_402_300_800, 0), None);
+        assert_eq!(DateTime::from_unix(0, 1_000_000_000), None);
+        assert_eq!(DateTime::from_unix(-1, 0).unwrap().to_string(), "1969-12-31T23:59:59Z");
+    }
+}
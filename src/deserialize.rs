@@ -0,0 +1,296 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::parse::{parse_spanned_value_at, unescape_into, ParseError, ParseOptions, TokenParseError};
+use crate::tokenize::{tokenize_with_spans, Span, Token};
+use crate::Value;
+
+/// Parses `input` by handing a [`Deserializer`] to `f`, which reads the document one typed
+/// piece at a time to build whatever `T` it likes, without an intermediate [`Value`] tree.
+/// The whole document has to be read, anything `f` leaves over is an error.
+pub fn parse_into<T, F>(input: &str, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce(&mut Deserializer) -> Result<T, ParseError>,
+{
+    let (tokens, spans) = tokenize_with_spans(input, &ParseOptions::default())?;
+    let mut deserializer = Deserializer {
+        tokens,
+        spans,
+        index: 0,
+        stack: Vec::new(),
+    };
+    let value = f(&mut deserializer)?;
+    if deserializer.index < deserializer.tokens.len() {
+        return Err(TokenParseError::TrailingData {
+            position: deserializer.position(),
+        }
+        .into());
+    }
+    Ok(value)
+}
+
+/// Reads a document as typed pieces, see [`parse_into`]. Every `expect_*` method reads the
+/// next value and fails with [`TokenParseError::UnexpectedToken`] when it's of another kind.
+/// Commas between entries and elements are handled by [`Deserializer::next_key`] and
+/// [`Deserializer::next_element`].
+pub struct Deserializer {
+    tokens: Vec<Token>,
+    spans: Vec<Span>,
+    index: usize,
+    /// For each open container, whether nothing has been read from it yet
+    stack: Vec<bool>,
+}
+
+impl Deserializer {
+    /// Byte offset of the next token, or the end of the input once all are read
+    pub fn position(&self) -> usize {
+        match self.spans.get(self.index) {
+            Some(span) => span.start,
+            None => self.spans.last().map_or(0, |span| span.end),
+        }
+    }
+
+    fn peek(&self) -> Result<&Token, TokenParseError> {
+        self.tokens.get(self.index).ok_or(TokenParseError::UnexpectedEof)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        TokenParseError::UnexpectedToken {
+            expected,
+            position: self.position(),
+        }
+        .into()
+    }
+
+    /// Reads the `{` starting an object, whose entries are then read with
+    /// [`Deserializer::next_key`] or [`Deserializer::expect_field`]
+    pub fn expect_object(&mut self) -> Result<(), ParseError> {
+        if *self.peek()? != Token::LeftBrace {
+            return Err(self.unexpected("an object"));
+        }
+        self.index += 1;
+        self.stack.push(true);
+        Ok(())
+    }
+
+    /// Reads the key of the next object entry, leaving its value to be read next. Returns
+    /// `None` once the object's closing `}` is read.
+    pub fn next_key(&mut self) -> Result<Option<String>, ParseError> {
+        if *self.peek()? == Token::RightBrace {
+            self.index += 1;
+            self.stack.pop();
+            return Ok(None);
+        }
+        self.separator()?;
+
+        let Token::String(raw) = self.peek()? else {
+            return Err(self.unexpected("a key"));
+        };
+        let mut key = String::new();
+        unescape_into(raw, &mut key)?;
+        self.index += 1;
+        if *self.peek()? != Token::Colon {
+            return Err(self.unexpected("a colon"));
+        }
+        self.index += 1;
+        Ok(Some(key))
+    }
+
+    /// Reads the key of the next object entry, which has to be `name`
+    pub fn expect_field(&mut self, name: &str) -> Result<(), ParseError> {
+        let key = self.next_key()?;
+        // back to the key before its colon, or to the closing brace
+        let position = self.spans[self.index - if key.is_some() { 2 } else { 1 }].start;
+        match key {
+            Some(key) if key == name => Ok(()),
+            _ => Err(TokenParseError::ExpectedField {
+                name: String::from(name),
+                position,
+            }
+            .into()),
+        }
+    }
+
+    /// Reads the `}` ending an object that has no entries left
+    pub fn end_object(&mut self) -> Result<(), ParseError> {
+        match self.peek()? {
+            Token::RightBrace => self.next_key().map(|_| ()),
+            _ => Err(self.unexpected("the end of the object")),
+        }
+    }
+
+    /// Reads the `[` starting an array, whose elements are then read after each call to
+    /// [`Deserializer::next_element`]
+    pub fn expect_array(&mut self) -> Result<(), ParseError> {
+        if *self.peek()? != Token::LeftBracket {
+            return Err(self.unexpected("an array"));
+        }
+        self.index += 1;
+        self.stack.push(true);
+        Ok(())
+    }
+
+    /// Whether the array has another element, which is then read next. Returns `false` once
+    /// the array's closing `]` is read.
+    pub fn next_element(&mut self) -> Result<bool, ParseError> {
+        if *self.peek()? == Token::RightBracket {
+            self.index += 1;
+            self.stack.pop();
+            return Ok(false);
+        }
+        self.separator()?;
+        Ok(true)
+    }
+
+    /// Reads the comma before every entry or element of the innermost container but its first
+    fn separator(&mut self) -> Result<(), ParseError> {
+        let Some(first) = self.stack.last_mut() else {
+            return Ok(());
+        };
+        if core::mem::replace(first, false) {
+            return Ok(());
+        }
+        if *self.peek()? != Token::Comma {
+            return Err(self.unexpected("a comma"));
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    pub fn expect_string(&mut self) -> Result<String, ParseError> {
+        match self.peek()? {
+            Token::String(_) => match self.value()? {
+                Value::String(string) => Ok(string),
+                _ => unreachable!("a string token parses as a string"),
+            },
+            _ => Err(self.unexpected("a string")),
+        }
+    }
+
+    /// Reads a number, rounding integers `f64` can't represent exactly to the closest one
+    pub fn expect_number(&mut self) -> Result<f64, ParseError> {
+        let number = match self.peek()? {
+            Token::Number(number) => *number,
+            Token::Integer(integer) => *integer as f64,
+            _ => return Err(self.unexpected("a number")),
+        };
+        self.index += 1;
+        Ok(number)
+    }
+
+    pub fn expect_bool(&mut self) -> Result<bool, ParseError> {
+        let boolean = match self.peek()? {
+            Token::True => true,
+            Token::False => false,
+            _ => return Err(self.unexpected("a boolean")),
+        };
+        self.index += 1;
+        Ok(boolean)
+    }
+
+    /// Reads a whole value of any kind, containers included
+    pub fn value(&mut self) -> Result<Value, ParseError> {
+        Ok(parse_spanned_value_at(&self.tokens, &self.spans, &mut self.index)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct User {
+        name: String,
+        age: f64,
+        admin: bool,
+        tags: Vec<String>,
+    }
+
+    fn user(de: &mut Deserializer) -> Result<User, ParseError> {
+        de.expect_object()?;
+        de.expect_field("name")?;
+        let name = de.expect_string()?;
+        de.expect_field("age")?;
+        let age = de.expect_number()?;
+        de.expect_field("admin")?;
+        let admin = de.expect_bool()?;
+        de.expect_field("tags")?;
+        let mut tags = Vec::new();
+        de.expect_array()?;
+        while de.next_element()? {
+            tags.push(de.expect_string()?);
+        }
+        de.end_object()?;
+        Ok(User { name, age, admin, tags })
+    }
+
+    #[test]
+    fn test_parse_into_struct() {
+        let input = r#"{"name": "Ada\n", "age": 36, "admin": true, "tags": ["a", "b"]}"#;
+        let expected = User {
+            name: "Ada\n".into(),
+            age: 36.0,
+            admin: true,
+            tags: vec!["a".into(), "b".into()],
+        };
+        assert_eq!(parse_into(input, user), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_into_any_order() {
+        let input = r#"{"b": [1, {"x": null}], "a": 2}"#;
+        let entries = parse_into(input, |de| {
+            de.expect_object()?;
+            let mut entries = Vec::new();
+            while let Some(key) = de.next_key()? {
+                entries.push((key, de.value()?));
+            }
+            Ok(entries)
+        });
+        let expected = vec![
+            ("b".into(), crate::parse(r#"[1, {"x": null}]"#).unwrap()),
+            ("a".into(), Value::Number(2.0)),
+        ];
+        assert_eq!(entries, Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_into_errors() {
+        let wrong_type = r#"{"name": "Ada", "age": "36", "admin": true, "tags": []}"#;
+        assert_eq!(
+            parse_into(wrong_type, user),
+            Err(TokenParseError::UnexpectedToken {
+                expected: "a number",
+                position: 23
+            }
+            .into())
+        );
+
+        let wrong_field = r#"{"name": "Ada", "admin": true}"#;
+        assert_eq!(
+            parse_into(wrong_field, user),
+            Err(TokenParseError::ExpectedField {
+                name: "age".into(),
+                position: 16
+            }
+            .into())
+        );
+
+        assert_eq!(
+            parse_into("[1] 2", |de| de.value()),
+            Err(TokenParseError::TrailingData { position: 4 }.into())
+        );
+        let numbers = |de: &mut Deserializer| {
+            de.expect_array()?;
+            while de.next_element()? {
+                de.expect_number()?;
+            }
+            Ok(())
+        };
+        let missing_comma = TokenParseError::UnexpectedToken {
+            expected: "a comma",
+            position: 3,
+        };
+        assert_eq!(parse_into("[1 2]", numbers), Err(missing_comma.into()));
+    }
+}
@@ -0,0 +1,98 @@
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+use crate::path::push_pointer_segment;
+use crate::serialize::to_string;
+use crate::Value;
+
+/// Describes how `b` differs from `a` for humans, one line per difference, ex.
+///
+/// ```text
+/// ~ /user/age: 41 -> 42
+/// - /user/nickname: "bob"
+/// + /tags/2: "new"
+/// ```
+///
+/// Each line names the JSON Pointer of a value that was changed (`~`), removed (`-`) or
+/// added (`+`), followed by the value(s) written as compact JSON. Equal documents produce
+/// an empty string.
+pub fn pretty_diff(a: &Value, b: &Value) -> String {
+    let mut output = String::new();
+    diff_into(&mut output, &mut String::new(), a, b);
+    output
+}
+
+fn diff_into(output: &mut String, pointer: &mut String, a: &Value, b: &Value) {
+    if a == b {
+        return;
+    }
+
+    let len = pointer.len();
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, a_value) in a {
+                push_pointer_segment(pointer, key);
+                match b.get(key) {
+                    Some(b_value) => diff_into(output, pointer, a_value, b_value),
+                    None => write_line(output, '-', pointer, a_value, None),
+                }
+                pointer.truncate(len);
+            }
+            for (key, b_value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                push_pointer_segment(pointer, key);
+                write_line(output, '+', pointer, b_value, None);
+                pointer.truncate(len);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                push_pointer_segment(pointer, &i.to_string());
+                match (a.get(i), b.get(i)) {
+                    (Some(a_value), Some(b_value)) => diff_into(output, pointer, a_value, b_value),
+                    (Some(a_value), None) => write_line(output, '-', pointer, a_value, None),
+                    (None, Some(b_value)) => write_line(output, '+', pointer, b_value, None),
+                    (None, None) => {}
+                }
+                pointer.truncate(len);
+            }
+        }
+        _ => write_line(output, '~', pointer, a, Some(b)),
+    }
+}
+
+fn write_line(output: &mut String, marker: char, pointer: &str, value: &Value, new_value: Option<&Value>) {
+    let pointer = if pointer.is_empty() { "(root)" } else { pointer };
+    let _ = write!(output, "{} {}: {}", marker, pointer, to_string(value));
+    if let Some(new_value) = new_value {
+        let _ = write!(output, " -> {}", to_string(new_value));
+    }
+    output.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_pretty_diff() {
+        let a = parse(r#"{"user": {"age": 41, "nickname": "bob", "a/b": 1}, "tags": ["x", "y"]}"#).unwrap();
+        let b = parse(r#"{"user": {"age": 42, "a/b": [1]}, "tags": ["x", "y", "new"], "extra": null}"#).unwrap();
+
+        let expected = concat!(
+            "+ /tags/2: \"new\"\n",
+            "~ /user/a~1b: 1 -> [1]\n",
+            "~ /user/age: 41 -> 42\n",
+            "- /user/nickname: \"bob\"\n",
+            "+ /extra: null\n",
+        );
+        assert_eq!(pretty_diff(&a, &b), expected);
+    }
+
+    #[test]
+    fn test_pretty_diff_equal_and_root() {
+        let a = parse(r#"{"a": [1, 2]}"#).unwrap();
+        assert_eq!(pretty_diff(&a, &a.clone()), "");
+        assert_eq!(pretty_diff(&Value::Null, &Value::Boolean(true)), "~ (root): null -> true\n");
+    }
+}
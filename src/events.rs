@@ -0,0 +1,191 @@
+use std::io::Write;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::serialize::{to_writer, write_escaped, SerializeError, SerializeOptions};
+use crate::Value;
+
+/// Writes compact JSON to an `io::Write` one event at a time, without building a [`Value`]
+/// tree first. Commas and colons are inserted automatically, and events that would make the
+/// document invalid, like a key outside an object, are rejected with a [`SerializeError`].
+pub struct Serializer<W> {
+    writer: W,
+    stack: Vec<Container>,
+    complete: bool,
+}
+
+/// A container that has been started but not yet ended
+enum Container {
+    Array { first: bool },
+    Object { first: bool, after_key: bool },
+}
+
+impl<W: Write> Serializer<W> {
+    pub fn new(writer: W) -> Self {
+        Serializer {
+            writer,
+            stack: Vec::new(),
+            complete: false,
+        }
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), SerializeError> {
+        self.before_value()?;
+        self.stack.push(Container::Object {
+            first: true,
+            after_key: false,
+        });
+        self.write(b"{")
+    }
+
+    pub fn end_object(&mut self) -> Result<(), SerializeError> {
+        match self.stack.last() {
+            Some(Container::Object { after_key: false, .. }) => self.end(b"}"),
+            _ => Err(SerializeError::UnexpectedEnd),
+        }
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), SerializeError> {
+        self.before_value()?;
+        self.stack.push(Container::Array { first: true });
+        self.write(b"[")
+    }
+
+    pub fn end_array(&mut self) -> Result<(), SerializeError> {
+        match self.stack.last() {
+            Some(Container::Array { .. }) => self.end(b"]"),
+            _ => Err(SerializeError::UnexpectedEnd),
+        }
+    }
+
+    /// Writes the key of the next object entry, which must be followed by its value
+    pub fn key(&mut self, key: &str) -> Result<(), SerializeError> {
+        let Some(Container::Object { first, after_key }) = self.stack.last_mut() else {
+            return Err(SerializeError::UnexpectedKey);
+        };
+        if *after_key {
+            return Err(SerializeError::UnexpectedKey);
+        }
+        let comma = !core::mem::replace(first, false);
+        *after_key = true;
+
+        let mut text = String::with_capacity(key.len() + 4);
+        if comma {
+            text.push(',');
+        }
+        write_escaped(&mut text, key).expect("writing to a String cannot fail");
+        text.push(':');
+        self.write(text.as_bytes())
+    }
+
+    /// Writes a whole value, as an array element, an object entry's value or the document
+    pub fn value(&mut self, value: &Value) -> Result<(), SerializeError> {
+        self.before_value()?;
+        to_writer(&mut self.writer, value, &SerializeOptions::default())
+            .map_err(|e| SerializeError::Io(e.kind()))?;
+        self.after_value();
+        Ok(())
+    }
+
+    /// Returns the writer once the document is complete
+    pub fn finish(self) -> Result<W, SerializeError> {
+        if self.complete {
+            Ok(self.writer)
+        } else {
+            Err(SerializeError::Incomplete)
+        }
+    }
+
+    /// Checks a value may come next, writing the comma separating it from the previous one
+    fn before_value(&mut self) -> Result<(), SerializeError> {
+        match self.stack.last_mut() {
+            None if self.complete => Err(SerializeError::DocumentComplete),
+            None => Ok(()),
+            Some(Container::Object { after_key: false, .. }) => Err(SerializeError::ExpectedKey),
+            Some(Container::Object { after_key, .. }) => {
+                *after_key = false;
+                Ok(())
+            }
+            Some(Container::Array { first }) => {
+                if core::mem::replace(first, false) {
+                    Ok(())
+                } else {
+                    self.write(b",")
+                }
+            }
+        }
+    }
+
+    fn after_value(&mut self) {
+        if self.stack.is_empty() {
+            self.complete = true;
+        }
+    }
+
+    fn end(&mut self, bracket: &[u8]) -> Result<(), SerializeError> {
+        self.stack.pop();
+        self.write(bracket)?;
+        self.after_value();
+        Ok(())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), SerializeError> {
+        self.writer.write_all(bytes).map_err(|e| SerializeError::Io(e.kind()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, to_string};
+
+    #[test]
+    fn test_serializer_events() {
+        let mut serializer = Serializer::new(Vec::new());
+        serializer.begin_object().unwrap();
+        serializer.key("name").unwrap();
+        serializer.value(&Value::String("a \"b\"".into())).unwrap();
+        serializer.key("items").unwrap();
+        serializer.begin_array().unwrap();
+        serializer.value(&Value::Number(1.0)).unwrap();
+        serializer.begin_object().unwrap();
+        serializer.end_object().unwrap();
+        serializer.begin_array().unwrap();
+        serializer.end_array().unwrap();
+        serializer.value(&parse(r#"{"x": [null]}"#).unwrap()).unwrap();
+        serializer.end_array().unwrap();
+        serializer.end_object().unwrap();
+
+        let output = String::from_utf8(serializer.finish().unwrap()).unwrap();
+        let expected = parse(r#"{"name": "a \"b\"", "items": [1, {}, [], {"x": [null]}]}"#).unwrap();
+        assert_eq!(output, r#"{"name":"a \"b\"","items":[1,{},[],{"x":[null]}]}"#);
+        assert_eq!(parse(&output).unwrap(), expected);
+
+        let mut serializer = Serializer::new(Vec::new());
+        serializer.value(&Value::Boolean(true)).unwrap();
+        assert_eq!(serializer.finish().unwrap(), to_string(&Value::Boolean(true)).into_bytes());
+    }
+
+    #[test]
+    fn test_serializer_misuse() {
+        let mut serializer = Serializer::new(Vec::new());
+        assert_eq!(serializer.key("a"), Err(SerializeError::UnexpectedKey));
+        assert_eq!(serializer.end_array(), Err(SerializeError::UnexpectedEnd));
+
+        serializer.begin_object().unwrap();
+        assert_eq!(serializer.value(&Value::Null), Err(SerializeError::ExpectedKey));
+        assert_eq!(serializer.begin_array(), Err(SerializeError::ExpectedKey));
+        assert_eq!(serializer.end_array(), Err(SerializeError::UnexpectedEnd));
+        serializer.key("a").unwrap();
+        assert_eq!(serializer.key("b"), Err(SerializeError::UnexpectedKey));
+        assert_eq!(serializer.end_object(), Err(SerializeError::UnexpectedEnd));
+        serializer.value(&Value::Null).unwrap();
+        serializer.end_object().unwrap();
+        assert_eq!(serializer.begin_object(), Err(SerializeError::DocumentComplete));
+        assert_eq!(serializer.finish().unwrap(), br#"{"a":null}"#);
+
+        let mut serializer = Serializer::new(Vec::new());
+        serializer.begin_array().unwrap();
+        assert_eq!(serializer.finish().err(), Some(SerializeError::Incomplete));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{parse, to_string, Value};
+
+/// Fuzz target checking the parse/serialize round trip.
+///
+/// Inputs that fail to parse are ignored. Anything that parses must serialize to text that
+/// parses back into an equal value, otherwise this panics.
+pub fn fuzz_roundtrip(input: &str) {
+    let value = match parse(input) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+
+    // literals like `1e400` overflow to infinity, which JSON can only write as `null`
+    if has_non_finite(&value) {
+        return;
+    }
+
+    let text = to_string(&value);
+    match parse(&text) {
+        Ok(reparsed) => assert_eq!(reparsed, value, "round trip of {:?} through {:?}", input, text),
+        Err(e) => panic!("serialized {:?} as {:?} which fails to parse: {:?}", input, text, e),
+    }
+}
+
+fn has_non_finite(value: &Value) -> bool {
+    match value {
+        Value::Number(number) => !number.is_finite(),
+        Value::Array(array) => array.iter().any(has_non_finite),
+        Value::Object(map) => map.values().any(has_non_finite),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzz_roundtrip() {
+        let inputs = [
+            r#"{"a": [1, 2.5, -0, 1e-7, null], "b": {"c": "\u00e9\n\"x\""}}"#,
+            "123456789012345678901234567890",
+            "[true, false, [], {}]",
+            "1e400",
+            "[1e400, 2]",
+            "",
+            "[1, 2",
+            "{\"a\" 1}",
+            "\"\\ud800\"",
+            "nul",
+            "]",
+        ];
+        for input in inputs {
+            fuzz_roundtrip(input);
+        }
+    }
+}
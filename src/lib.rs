@@ -1,10 +1,74 @@
-use std::collections::HashMap;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+// the test harness links `std` either way, this only gives tests its macros without the feature
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::str::FromStr;
 
 mod tokenize;
+mod base64;
+mod convert;
+#[cfg(feature = "chrono")]
+mod datetime;
+mod deserialize;
+mod diff;
+#[cfg(feature = "std")]
+mod events;
+#[cfg(any(test, feature = "fuzzing"))]
+mod fuzz;
+mod ordered;
 mod parse;
+mod path;
+#[cfg(feature = "std")]
+mod reader;
+mod reformat;
+mod schema;
+mod serialize;
+mod stream;
+#[cfg(test)]
+mod test_alloc;
+mod validate;
+mod value;
 
+pub use convert::{FromValueError, TryFromValue};
+#[cfg(feature = "chrono")]
+pub use datetime::DateTime;
+pub use deserialize::{parse_into, Deserializer};
+pub use diff::pretty_diff;
+#[cfg(feature = "std")]
+pub use events::Serializer;
+#[cfg(feature = "fuzzing")]
+pub use fuzz::fuzz_roundtrip;
+pub use ordered::OrderedValue;
+pub use parse::{
+    parse, parse_chunks, parse_or_null, parse_ordered, parse_strict, parse_value_at, parse_with_diagnostics,
+    parse_with_options, parse_with_profile, parse_with_stats, unescape_into, Diagnostic, InvalidEscapePolicy,
+    NumberSuffixHandler, ParseError, ParseOptions, ParseProfile, ParseStats, TokenParseError,
+};
+pub use path::PathError;
+#[cfg(feature = "std")]
+pub use reader::{iter_reader, iter_reader_with_buffer_size};
+pub use reformat::{minify, prettify};
+pub use schema::Schema;
+pub use serialize::{
+    to_query_string, to_string, to_string_pretty, to_string_with_options, try_to_string_with_options, write_escaped,
+    SerializeError, SerializeOptions,
+};
+#[cfg(feature = "std")]
+pub use serialize::{to_writer, to_writer_with_limit};
+pub use stream::parse_array_stream;
+pub use tokenize::{parse_number, tokenize, tokenize_spanned, Span, Token, TokenizeError};
+pub use validate::validate_with_position;
+pub use value::{ArrayElementType, Entry, EqOptions, KeyError, ValueKind};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     /// literal characters `null`
     Null,
@@ -15,12 +79,126 @@ pub enum Value {
     /// a number, either integer or floating point
     Number(f64),
 
+    /// an integer `f64` can't represent exactly, ex. one beyond `i64` or a nanosecond
+    /// timestamp like `1700000000123456789`, stored exactly instead of rounded
+    Integer(i128),
+
+    /// a number kept as the exact text it was written with, ex. `1.50`, written back out
+    /// unchanged with [`SerializeOptions::preserve_number_text`]. Only produced by
+    /// [`ParseOptions::numbers_as_strings`].
+    RawNumber(String),
+
     /// a string of characters wrapped in double quotes
     String(String),
 
     /// an array of values
     Array(Vec<Value>),
 
-    /// an object with key-value pairs
-    Object(HashMap<String, Value>),
+    /// an object with key-value pairs, which always iterate in sorted key order whatever order
+    /// they were parsed or inserted in. Keys are shared strings so that repeated keys can
+    /// share one allocation, see [`ParseOptions::intern_keys`].
+    ///
+    /// JSON only has string keys, and the key type makes that hold for every object however it
+    /// was built: numbers or other values have to be converted to text before they can be used
+    /// as keys, so there is no invalid key to check for after construction, and
+    /// [`Value::ensure_string_keys`] always succeeds.
+    Object(BTreeMap<Arc<str>, Value>),
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+impl TryFrom<&str> for Value {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        parse(input)
+    }
+}
+
+impl TryFrom<String> for Value {
+    type Error = ParseError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        parse(&input)
+    }
+}
+
+/// Appends key-value pairs to an object, replacing the values of keys already present
+///
+/// # Panics
+///
+/// Panics if the value isn't an object.
+impl Extend<(String, Value)> for Value {
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        match self {
+            Value::Object(map) => map.extend(iter.into_iter().map(|(key, value)| (Arc::from(key), value))),
+            _ => panic!("Value::extend with key-value pairs called on a non-object value"),
+        }
+    }
+}
+
+/// Appends elements to an array
+///
+/// # Panics
+///
+/// Panics if the value isn't an array.
+impl Extend<Value> for Value {
+    fn extend<I: IntoIterator<Item = Value>>(&mut self, iter: I) {
+        match self {
+            Value::Array(array) => array.extend(iter),
+            _ => panic!("Value::extend with values called on a non-array value"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_str() {
+        let expected = Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(2.0),
+            Value::Number(3.0),
+        ]);
+        assert_eq!(Value::try_from("[1,2,3]").unwrap(), expected);
+        assert_eq!(Value::try_from(String::from("[1, 2, 3]")).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_try_from_malformed() {
+        assert_eq!(
+            Value::try_from("[1 2]"),
+            Err(ParseError::ParseError(TokenParseError::ExpectedComma))
+        );
+        assert_eq!(
+            Value::try_from("[nul]"),
+            Err(ParseError::TokenizeError(TokenizeError::UnfinishedLiteralValue))
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut object = parse(r#"{"a": 1, "b": 2}"#).unwrap();
+        object.extend([(String::from("b"), Value::Null), (String::from("c"), Value::Boolean(true))]);
+        assert_eq!(object, parse(r#"{"a": 1, "b": null, "c": true}"#).unwrap());
+
+        let mut array = parse("[1]").unwrap();
+        array.extend(vec![Value::Number(2.0), Value::String("3".into())]);
+        array.extend(core::iter::empty::<Value>());
+        assert_eq!(array, parse(r#"[1, 2, "3"]"#).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_extend_wrong_variant() {
+        Value::Array(vec![]).extend([(String::from("a"), Value::Null)]);
+    }
 }
@@ -0,0 +1,129 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{to_string, write_escaped, Value};
+
+/// A value whose objects keep their entries in the order they were written, as returned by
+/// [`parse_ordered`](crate::parse_ordered). Repeated keys are all kept.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderedValue {
+    /// Anything but an array or object
+    Scalar(Value),
+    Array(Vec<OrderedValue>),
+    Object(Vec<(String, OrderedValue)>),
+}
+
+impl OrderedValue {
+    /// Returns the entries of an object in source order, or `None` for anything else
+    pub fn into_entries(self) -> Option<Vec<(String, OrderedValue)>> {
+        match self {
+            OrderedValue::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Returns the entries of an object in their current order, or `None` for anything else
+    pub fn entries(&self) -> Option<&[(String, OrderedValue)]> {
+        match self {
+            OrderedValue::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Reorders the entries of every object in the tree by key, lexicographically. Repeated
+    /// keys keep their order relative to each other.
+    pub fn sort_keys(&mut self) {
+        match self {
+            OrderedValue::Scalar(_) => {}
+            OrderedValue::Array(array) => array.iter_mut().for_each(OrderedValue::sort_keys),
+            OrderedValue::Object(entries) => {
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                entries.iter_mut().for_each(|(_, value)| value.sort_keys());
+            }
+        }
+    }
+
+    /// Converts to a [`Value`], whose objects iterate in sorted key order. Of repeated keys
+    /// the last value wins, like [`parse`](crate::parse).
+    pub fn into_value(self) -> Value {
+        match self {
+            OrderedValue::Scalar(value) => value,
+            OrderedValue::Array(array) => Value::Array(array.into_iter().map(OrderedValue::into_value).collect()),
+            OrderedValue::Object(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Writes compact JSON text with object entries in their original order
+impl fmt::Display for OrderedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderedValue::Scalar(value) => f.write_str(&to_string(value)),
+            OrderedValue::Array(array) => {
+                f.write_str("[")?;
+                for (i, value) in array.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            OrderedValue::Object(entries) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_escaped(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{parse, parse_ordered};
+
+    #[test]
+    fn test_source_order() {
+        let input = r#"{"zeta":1,"alpha":{"y":[true,{"b":null,"a":"x"}],"x":2},"mid":"\n"}"#;
+        let ordered = parse_ordered(input).unwrap();
+        assert_eq!(ordered.to_string(), input);
+        assert_eq!(ordered.clone().into_value(), parse(input).unwrap());
+
+        let keys: Vec<String> = ordered.into_entries().unwrap().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["zeta", "alpha", "mid"]);
+    }
+
+    #[test]
+    fn test_repeated_keys() {
+        let ordered = parse_ordered(r#"{"b": 1, "a": 2, "b": 3}"#).unwrap();
+        assert_eq!(ordered.to_string(), r#"{"b":1,"a":2,"b":3}"#);
+        assert_eq!(ordered.into_value(), parse(r#"{"a": 2, "b": 3}"#).unwrap());
+        assert_eq!(parse_ordered("[1]").unwrap().into_entries(), None);
+        assert_eq!(parse_ordered(r#"{"a" 1}"#), Err(crate::TokenParseError::ExpectedColon.into()));
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut ordered = parse_ordered(r#"{"c": 1, "a": [{"z": 1, "y": 2}], "b": {"x": 1, "w": 2}, "a": 3}"#).unwrap();
+        ordered.sort_keys();
+        let keys: Vec<&str> = ordered.entries().unwrap().iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["a", "a", "b", "c"]);
+        assert_eq!(ordered.to_string(), r#"{"a":[{"y":2,"z":1}],"a":3,"b":{"w":2,"x":1},"c":1}"#);
+        assert_eq!(parse_ordered("[1]").unwrap().entries(), None);
+    }
+}
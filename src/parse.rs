@@ -1,19 +1,436 @@
-use std::collections::HashMap;
-use crate::tokenize::{Token, tokenize, TokenizeError};
-use crate::Value;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use crate::path::{push_pointer_segment, resolve_pointer};
+use crate::tokenize::{inexact_integer, Span, Token, tokenize_with_spans, TokenizeError};
+use crate::{OrderedValue, Value};
 
 // suggestion: put this near the top, just below `mod` and `use` statements
-pub fn parse(input: String) -> Result<Value, ParseError> {
-    let tokens = tokenize(input)?;
-    let value = parse_tokens(&tokens, &mut 0)?;
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    if let Some(value) = parse_scalar(input) {
+        return Ok(value);
+    }
+    parse_with_options(input, &ParseOptions::default())
+}
+
+/// Fast path for input that is a single literal, number or escape-free string, producing the
+/// value without collecting chars or tokens. Returns `None` when the input needs the full
+/// parser, including whenever it's invalid so the parser can report why.
+fn parse_scalar(input: &str) -> Option<Value> {
+    let input = input.trim_matches(|c: char| c.is_ascii_whitespace());
+    match input {
+        "null" => return Some(Value::Null),
+        "true" => return Some(Value::Boolean(true)),
+        "false" => return Some(Value::Boolean(false)),
+        _ => {}
+    }
+
+    let bytes = input.as_bytes();
+    if bytes.len() >= 2 && bytes[0] == b'"' && bytes[bytes.len() - 1] == b'"' {
+        let contents = &input[1..input.len() - 1];
+        if contents.contains(['"', '\\']) {
+            return None;
+        }
+        return Some(Value::String(String::from(contents)));
+    }
+
+    // the same rules as the tokenizer: anything `f64` parses from these characters is a number
+    // the tokenizer reads in full, and integers `f64` would round are kept exact
+    let digits = bytes.strip_prefix(b"-").unwrap_or(bytes);
+    if !digits.first()?.is_ascii_digit() || !bytes.iter().all(|byte| b"0123456789.eE+-".contains(byte)) {
+        return None;
+    }
+    if !input.contains(['.', 'e', 'E']) {
+        if let Some(integer) = inexact_integer(input) {
+            return Some(Value::Integer(integer));
+        }
+    }
+    input.parse().ok().map(Value::Number)
+}
+
+/// Turns a number with a unit suffix, ex. `10s`, into a value. Gets the whole text of the
+/// number including its suffix.
+#[derive(Debug, Clone, Copy)]
+pub struct NumberSuffixHandler(pub fn(&str) -> Result<Value, TokenizeError>);
+
+impl PartialEq for NumberSuffixHandler {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::fn_addr_eq(self.0, other.0)
+    }
+}
+
+/// Options controlling what [`parse_with_options`] accepts
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Only accept an object or array as the top-level value, as RFC 4627 required
+    pub require_top_level_container: bool,
+    /// Turn object keys containing `.` into nested objects, ex. `{"a.b": 1}` into `{"a": {"b": 1}}`
+    pub expand_dotted_keys: bool,
+    /// Expected number of tokens in the input, used to size the token buffer up front
+    pub token_capacity: usize,
+    /// Expected length of a typical string in the input, used to size string buffers up front
+    pub string_capacity: usize,
+    /// Accept the non-standard `NaN` literal
+    pub allow_nan: bool,
+    /// Accept the non-standard `Infinity` and `-Infinity` literals
+    pub allow_infinity: bool,
+    /// Skip `// line` and `/* block */` comments like whitespace
+    pub allow_comments: bool,
+    /// Accept a comma after the last element of an array or the last member of an object
+    pub allow_trailing_commas: bool,
+    /// Accept strings wrapped in single quotes, ex. `'text'`
+    pub allow_single_quotes: bool,
+    /// Accept object keys written as bare identifiers, ex. `{key: 1}`
+    pub allow_unquoted_keys: bool,
+    /// Reject escapes JSON doesn't define, like `\q`. When off, the backslash is dropped and
+    /// the escaped character kept.
+    pub strict_escapes: bool,
+    /// Share one allocation between all object keys with the same text, cutting memory and
+    /// allocations for documents like arrays of records that repeat the same keys
+    pub intern_keys: bool,
+    /// Accept numbers followed by a unit suffix, ex. `10s` or `5MB`, handing the whole text to
+    /// this function to turn into a value. Without a handler such suffixes are an error.
+    pub number_suffix_handler: Option<NumberSuffixHandler>,
+    /// JSON Pointers, ex. `/users/0/id`, to values that must be integers. Anything else at one
+    /// of these paths, including a number with a fractional part, is an
+    /// [`ExpectedInteger`](TokenParseError::ExpectedInteger) error. Paths not in the document are ignored.
+    pub integer_paths: Vec<String>,
+    /// Give up with [`ParseError::BudgetExceeded`] once the input has more than this many
+    /// tokens, bounding the work done on untrusted input however shallow it is
+    pub max_tokens: Option<usize>,
+    /// Read a missing array element, ex. between the commas of `["a", , "b"]` or after the
+    /// last comma of `["a",]`, as `null` instead of an error. Trailing commas are still
+    /// skipped when [`allow_trailing_commas`](Self::allow_trailing_commas) is set.
+    pub empty_slots_as_null: bool,
+    /// Reject an object key given more than once instead of keeping its last value
+    pub reject_duplicate_keys: bool,
+    /// Reject tokens left over after the top-level value, ex. the `2` in `[1] 2`
+    pub reject_trailing_data: bool,
+    /// Reject numbers the JSON grammar doesn't allow but the tokenizer otherwise reads, ex.
+    /// `01` or `1.`
+    pub strict_numbers: bool,
+    /// Reject unescaped control characters, U+0000 to U+001F, inside strings
+    pub reject_control_characters: bool,
+    /// Keep every number as [`Value::RawNumber`] holding its source text instead of converting
+    /// it, so numbers like `1.50` or `1e400` can be written back out byte for byte with
+    /// [`SerializeOptions::preserve_number_text`](crate::SerializeOptions::preserve_number_text)
+    pub numbers_as_strings: bool,
+    /// Lowercase every object key as it's read. Keys that only differ by case count as
+    /// duplicates, see [`reject_duplicate_keys`](Self::reject_duplicate_keys).
+    pub lowercase_keys: bool,
+    /// What escapes JSON doesn't define, like `\q`, turn into when
+    /// [`strict_escapes`](Self::strict_escapes) is off
+    pub invalid_escape_policy: InvalidEscapePolicy,
+    /// Also accept this character as the decimal point of numbers, ex. `,` for `3,14`. It's
+    /// only read as part of a number when it comes straight after a digit of one, before any
+    /// other decimal point or exponent, and is followed by another digit. A `,` directly inside
+    /// an array always separates elements, so `[1,2]` is still `[1, 2]`, and a decimal comma
+    /// only works for top-level numbers and object values, ex. `{"pi": 3,14}`. `.` keeps
+    /// working, and the default is `.` alone.
+    pub decimal_separator: char,
+    /// Only skip the whitespace RFC 8259 allows between tokens: space, tab, line feed and
+    /// carriage return. Otherwise form feed is skipped too.
+    pub strict_whitespace: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            require_top_level_container: false,
+            expand_dotted_keys: false,
+            token_capacity: 0,
+            string_capacity: 0,
+            allow_nan: false,
+            allow_infinity: false,
+            allow_comments: false,
+            allow_trailing_commas: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            strict_escapes: false,
+            intern_keys: false,
+            number_suffix_handler: None,
+            integer_paths: Vec::new(),
+            max_tokens: None,
+            empty_slots_as_null: false,
+            reject_duplicate_keys: false,
+            reject_trailing_data: false,
+            strict_numbers: false,
+            reject_control_characters: false,
+            numbers_as_strings: false,
+            lowercase_keys: false,
+            invalid_escape_policy: InvalidEscapePolicy::default(),
+            decimal_separator: '.',
+            strict_whitespace: false,
+        }
+    }
+}
+
+/// How [`ParseOptions::invalid_escape_policy`] reads an escape JSON doesn't define, ex. `\q`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum InvalidEscapePolicy {
+    /// Drop the backslash and keep the character, `\q` reads as `q`
+    #[default]
+    KeepCharacter,
+    /// Keep the escape as written, `\q` reads as `\q`
+    KeepBackslash,
+    /// Replace the whole escape with this character, ex. U+FFFD
+    Replace(char),
+}
+
+/// Preset [`ParseOptions`] for common kinds of input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseProfile {
+    /// Standard JSON, what [`parse`] accepts
+    Json,
+    /// The relaxations of JSON5 that hand-written config files use most:
+    ///
+    /// - `// line` and `/* block */` comments
+    /// - trailing commas in arrays and objects
+    /// - single-quoted strings
+    /// - unquoted object keys made of letters, digits, `_` and `$`
+    ///
+    /// Other JSON5 features are not included: hexadecimal numbers, leading or trailing decimal
+    /// points, explicit `+` signs, `NaN` and `Infinity` (see [`ParseOptions::allow_nan`] and
+    /// [`ParseOptions::allow_infinity`]), and strings continued over several lines.
+    Json5Lite,
+    /// Exactly RFC 8259 and nothing more: on top of [`ParseProfile::Json`], duplicate keys,
+    /// trailing data, numbers like `01`, raw control characters in strings, escapes like `\q`
+    /// and form feeds between tokens are errors
+    Strict,
+}
+
+impl ParseProfile {
+    pub fn options(&self) -> ParseOptions {
+        match self {
+            ParseProfile::Json => ParseOptions::default(),
+            ParseProfile::Json5Lite => ParseOptions {
+                allow_comments: true,
+                allow_trailing_commas: true,
+                allow_single_quotes: true,
+                allow_unquoted_keys: true,
+                ..ParseOptions::default()
+            },
+            ParseProfile::Strict => ParseOptions {
+                reject_duplicate_keys: true,
+                reject_trailing_data: true,
+                strict_numbers: true,
+                reject_control_characters: true,
+                strict_escapes: true,
+                strict_whitespace: true,
+                ..ParseOptions::default()
+            },
+        }
+    }
+}
+
+pub fn parse_with_profile(input: &str, profile: ParseProfile) -> Result<Value, ParseError> {
+    parse_with_options(input, &profile.options())
+}
+
+/// Like [`parse`], but with [`ParseProfile::Strict`], rejecting anything RFC 8259 doesn't allow
+pub fn parse_strict(input: &str) -> Result<Value, ParseError> {
+    parse_with_profile(input, ParseProfile::Strict)
+}
+
+pub fn parse_with_options(input: &str, options: &ParseOptions) -> Result<Value, ParseError> {
+    let (tokens, spans) = tokenize_with_spans(input, options)?;
+    if options.require_top_level_container
+        && !matches!(tokens.first(), Some(Token::LeftBrace | Token::LeftBracket))
+    {
+        return Err(TokenParseError::TopLevelMustBeContainer.into());
+    }
+    let mut parser = Parser::new(&tokens, Some(&spans), options);
+    let mut value = parser.parse_value()?;
+    if options.reject_trailing_data && parser.index < tokens.len() {
+        return Err(TokenParseError::TrailingData { position: parser.position() }.into());
+    }
+    if options.expand_dotted_keys {
+        value = expand_dotted_keys(value)?;
+    }
+    for pointer in &options.integer_paths {
+        match resolve_pointer(&value, pointer) {
+            Some(Value::Integer(_)) | None => {}
+            Some(Value::Number(number)) if number.is_finite() && number % 1.0 == 0.0 => {}
+            Some(value @ Value::RawNumber(_)) if value.as_i128().is_some() => {}
+            Some(_) => return Err(TokenParseError::ExpectedInteger(pointer.clone()).into()),
+        }
+    }
     Ok(value)
 }
 
+/// Expands dotted object keys into nested objects throughout `value`, merging objects that
+/// end up under the same key. A key that is both a value and a prefix of another key, or
+/// that is given twice, is a conflict.
+fn expand_dotted_keys(value: Value) -> ParseResult {
+    match value {
+        Value::Array(array) => array
+            .into_iter()
+            .map(expand_dotted_keys)
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(map) => {
+            let mut expanded = BTreeMap::new();
+            for (key, value) in map {
+                let value = expand_dotted_keys(value)?;
+                let path: Vec<&str> = key.split('.').collect();
+                insert_dotted(&mut expanded, &path, value, &key)?;
+            }
+            Ok(Value::Object(expanded))
+        }
+        scalar => Ok(scalar),
+    }
+}
+
+fn insert_dotted(
+    map: &mut BTreeMap<Arc<str>, Value>,
+    path: &[&str],
+    value: Value,
+    key: &str,
+) -> Result<(), TokenParseError> {
+    let conflict = || TokenParseError::DottedKeyConflict(key.into());
+    let (first, rest) = path.split_first().ok_or_else(conflict)?;
+
+    if !rest.is_empty() {
+        let nested = map
+            .entry(Arc::from(*first))
+            .or_insert_with(|| Value::Object(BTreeMap::new()));
+        return match nested {
+            Value::Object(nested) => insert_dotted(nested, rest, value, key),
+            _ => Err(conflict()),
+        };
+    }
+
+    match (map.get_mut(*first), value) {
+        (None, value) => {
+            map.insert(Arc::from(*first), value);
+            Ok(())
+        }
+        // an object given both as a whole and through dotted keys, ex. `{"a": {"b": 1}, "a.c": 2}`
+        (Some(Value::Object(existing)), Value::Object(value)) => {
+            for (nested_key, nested_value) in value {
+                insert_dotted(existing, &[&nested_key], nested_value, key)?;
+            }
+            Ok(())
+        }
+        _ => Err(conflict()),
+    }
+}
+
+/// Diagnostics about a document, gathered by [`parse_with_stats`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseStats {
+    /// Number of tokens in the input
+    pub tokens: usize,
+    /// Number of bytes of input up to the end of the parsed value
+    pub bytes_consumed: usize,
+    /// Deepest nesting of arrays and objects, `0` for a lone scalar
+    pub max_depth: usize,
+    /// Number of string values, object keys aren't counted
+    pub strings: usize,
+    /// Number of number values
+    pub numbers: usize,
+    /// Number of arrays and objects
+    pub containers: usize,
+}
+
+impl ParseStats {
+    fn record(&mut self, value: &Value, depth: usize) {
+        match value {
+            Value::String(_) => self.strings += 1,
+            Value::Number(_) | Value::Integer(_) | Value::RawNumber(_) => self.numbers += 1,
+            Value::Array(array) => {
+                self.containers += 1;
+                self.max_depth = self.max_depth.max(depth + 1);
+                array.iter().for_each(|value| self.record(value, depth + 1));
+            }
+            Value::Object(map) => {
+                self.containers += 1;
+                self.max_depth = self.max_depth.max(depth + 1);
+                map.values().for_each(|value| self.record(value, depth + 1));
+            }
+            Value::Null | Value::Boolean(_) => {}
+        }
+    }
+}
+
+/// Like [`parse`], also returning [`ParseStats`] describing the input
+pub fn parse_with_stats(input: &str) -> Result<(Value, ParseStats), ParseError> {
+    let options = ParseOptions::default();
+    let (tokens, spans) = tokenize_with_spans(input, &options)?;
+    let mut parser = Parser::new(&tokens, Some(&spans), &options);
+    let value = parser.parse_value()?;
+
+    let mut stats = ParseStats {
+        tokens: tokens.len(),
+        bytes_consumed: spans[parser.index - 1].end,
+        ..ParseStats::default()
+    };
+    stats.record(&value, 0);
+    Ok((value, stats))
+}
+
+/// Something questionable about the input that didn't stop it from parsing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    /// An object key appeared more than once, the last value was kept
+    DuplicateKey {
+        key: String,
+        /// Byte offset of the repeated key
+        position: usize,
+    },
+}
+
+/// Like [`parse`], also returning a [`Diagnostic`] for everything questionable that was accepted
+pub fn parse_with_diagnostics(input: &str) -> Result<(Value, Vec<Diagnostic>), ParseError> {
+    let options = ParseOptions::default();
+    let (tokens, spans) = tokenize_with_spans(input, &options)?;
+    let mut parser = Parser::new(&tokens, Some(&spans), &options);
+    let value = parser.parse_value()?;
+    Ok((value, parser.diagnostics))
+}
+
+/// Like [`parse`], but empty or whitespace-only input yields `Value::Null` instead of an error
+pub fn parse_or_null(input: &str) -> Result<Value, ParseError> {
+    if input.chars().all(|ch| ch.is_ascii_whitespace()) {
+        return Ok(Value::Null);
+    }
+    parse(input)
+}
+
+/// Like [`parse`], keeping object entries in the order they appear in the source rather
+/// than sorted, so documents can be written back out with their keys in the same order
+pub fn parse_ordered(input: &str) -> Result<OrderedValue, ParseError> {
+    let options = ParseOptions::default();
+    let (tokens, spans) = tokenize_with_spans(input, &options)?;
+    let mut parser = Parser::new(&tokens, Some(&spans), &options);
+    Ok(parser.parse_ordered()?)
+}
+
+/// Parses a document delivered as separate chunks of UTF-8 bytes. Chunks may split a
+/// character anywhere, the bytes are only validated once joined.
+pub fn parse_chunks<I: IntoIterator<Item = Vec<u8>>>(chunks: I) -> Result<Value, ParseError> {
+    let mut bytes = Vec::new();
+    for chunk in chunks {
+        bytes.extend_from_slice(&chunk);
+    }
+    let input = core::str::from_utf8(&bytes).map_err(|_| ParseError::InvalidUtf8)?;
+    parse(input)
+}
+
 // suggestion: put this below the definition of `Value`
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
     TokenizeError(TokenizeError),
     ParseError(TokenParseError),
+    /// The input bytes weren't valid UTF-8
+    InvalidUtf8,
+    /// The input has more tokens than [`ParseOptions::max_tokens`] allows
+    BudgetExceeded,
+    /// Reading the input failed
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
 }
 
 impl From<TokenParseError> for ParseError {
@@ -32,44 +449,428 @@ impl From<TokenizeError> for ParseError {
 pub enum TokenParseError {
     /// An escape sequence was started without 4 hexadecimal digits afterward
     UnfinishedEscape,
+    /// A backslash was followed by a character JSON doesn't define an escape for
+    InvalidEscape(char),
     /// A character in an escape sequence was not valid hexadecimal
     InvalidHexValue,
-    /// Invalid unicode value
-    InvalidCodePointValue,
+    /// A `\u` escape decodes to something that isn't a unicode scalar value, like half of a
+    /// surrogate pair. `position` is where the escape starts, as a byte offset in the source
+    /// text, or the string's token index when parsing tokens directly.
+    InvalidCodePointValue { value: u32, position: usize },
     ExpectedComma,
+    /// A comma where a value or property should be, like the empty slot in `[1,,2]`.
+    /// `position` is the byte offset of the comma, or its token index when parsing tokens directly.
+    UnexpectedComma { position: usize },
     ExpectedProperty,
     ExpectedColon,
     ExpectedValue,
+    /// The top-level value was a scalar while a container was required
+    TopLevelMustBeContainer,
+    /// The input was expected to be an array
+    ExpectedArray,
+    /// Expanding dotted keys found this key both as a value and as a prefix, or twice
+    DottedKeyConflict(String),
+    /// The value at this JSON Pointer, one of [`ParseOptions::integer_paths`], isn't an integer
+    ExpectedInteger(String),
+    /// The tokens ran out in the middle of a value
+    UnexpectedEof,
+    /// A container was closed with the wrong kind of bracket, ex. `[1, 2}`
+    MismatchedBracket {
+        expected: char,
+        found: char,
+        /// Byte offset of the offending bracket
+        position: usize,
+    },
+    /// An object key was given twice while [`ParseOptions::reject_duplicate_keys`] is set
+    DuplicateKey {
+        key: String,
+        /// Byte offset of the repeated key
+        position: usize,
+    },
+    /// Tokens were left after the top-level value while [`ParseOptions::reject_trailing_data`]
+    /// is set. `position` is the byte offset of the first of them.
+    TrailingData { position: usize },
+    /// A [`Deserializer`](crate::Deserializer) found something else where it expected this,
+    /// ex. `"a number"`. `position` is the byte offset of what it found.
+    UnexpectedToken { expected: &'static str, position: usize },
+    /// A [`Deserializer`](crate::Deserializer) expected the object entry with this key
+    /// next. `position` is the byte offset of what it found instead.
+    ExpectedField { name: String, position: usize },
+    /// Parsing the value at `path`, a JSON Pointer, failed with `cause`. Wraps every error
+    /// from inside an array or object, ex. `{"a": [1, 2}]}` gives path `/a`.
+    InValue { path: String, cause: Box<TokenParseError> },
+}
+
+impl TokenParseError {
+    /// Adds `segment` to the front of the error's path, as it leaves the container holding
+    /// the value at `segment`
+    pub(crate) fn in_value(self, segment: &str) -> Self {
+        let mut path = String::new();
+        push_pointer_segment(&mut path, segment);
+        match self {
+            TokenParseError::InValue { path: rest, cause } => {
+                path.push_str(&rest);
+                TokenParseError::InValue { path, cause }
+            }
+            cause => TokenParseError::InValue {
+                path,
+                cause: Box::new(cause),
+            },
+        }
+    }
 }
 
 type ParseResult = Result<Value, TokenParseError>;
 
-fn parse_tokens(tokens: &Vec<Token>, index: &mut usize) -> ParseResult {
-    let token = &tokens[*index];
+/// Parses the value starting at token `index`, such as tokens from [`tokenize`](crate::tokenize),
+/// leaving `index` just past the value's last token. Positions in errors are token indices.
+pub fn parse_value_at(tokens: &[Token], index: &mut usize) -> ParseResult {
+    let options = ParseOptions::default();
+    let mut parser = Parser::new(tokens, None, &options);
+    parser.index = *index;
+    let value = parser.parse_value();
+    *index = parser.index;
+    value
+}
+
+/// Like [`parse_value_at`], with positions in errors taken from `spans`
+pub(crate) fn parse_spanned_value_at(tokens: &[Token], spans: &[Span], index: &mut usize) -> ParseResult {
+    let options = ParseOptions::default();
+    let mut parser = Parser::new(tokens, Some(spans), &options);
+    parser.index = *index;
+    let value = parser.parse_value();
+    *index = parser.index;
+    value
+}
+
+/// Walks a slice of tokens, building up values
+struct Parser<'a> {
+    tokens: &'a [Token],
+    /// Where each token sits in the source text, when the tokens came from text
+    spans: Option<&'a [Span]>,
+    options: &'a ParseOptions,
+    index: usize,
+    diagnostics: Vec<Diagnostic>,
+    /// Every key seen so far, when interning keys
+    keys: BTreeSet<Arc<str>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], spans: Option<&'a [Span]>, options: &'a ParseOptions) -> Self {
+        Parser {
+            tokens,
+            spans,
+            options,
+            index: 0,
+            diagnostics: Vec::new(),
+            keys: BTreeSet::new(),
+        }
+    }
+
+    fn peek(&self) -> Result<&'a Token, TokenParseError> {
+        self.tokens.get(self.index).ok_or(TokenParseError::UnexpectedEof)
+    }
+
+    /// Byte offset of the current token in the source text, or its token index when there
+    /// is no source text
+    fn position(&self) -> usize {
+        match self.spans {
+            Some(spans) => match spans.get(self.index) {
+                Some(span) => span.start,
+                None => spans.last().map_or(0, |span| span.end),
+            },
+            None => self.index,
+        }
+    }
+
+    /// Unescapes the string token at `token`, moving code point errors from an offset
+    /// within the string to a position in the source text
+    fn parse_string(&self, raw: &str, token: usize) -> Result<String, TokenParseError> {
+        parse_string(raw, self.options).map_err(|err| match err {
+            TokenParseError::InvalidCodePointValue { value, position } => {
+                let position = match self.spans {
+                    // skipping the opening quote
+                    Some(spans) => spans[token].start + 1 + position,
+                    None => token,
+                };
+                TokenParseError::InvalidCodePointValue { value, position }
+            }
+            err => err,
+        })
+    }
+
+    /// Builds the object key for the string token at `token`, reusing an identical earlier
+    /// key when interning. Keys without escapes are taken straight from the token text.
+    fn parse_key(&mut self, raw: &str, token: usize) -> Result<Arc<str>, TokenParseError> {
+        let unescaped;
+        let mut key = if raw.contains('\\') {
+            unescaped = self.parse_string(raw, token)?;
+            unescaped.as_str()
+        } else {
+            raw
+        };
+        let lowercased;
+        if self.options.lowercase_keys {
+            lowercased = key.to_lowercase();
+            key = &lowercased;
+        }
+
+        if !self.options.intern_keys {
+            return Ok(Arc::from(key));
+        }
+        if let Some(interned) = self.keys.get(key) {
+            return Ok(interned.clone());
+        }
+        let interned: Arc<str> = Arc::from(key);
+        self.keys.insert(interned.clone());
+        Ok(interned)
+    }
+
+    /// Error for a closing bracket that doesn't match the container being parsed
+    fn mismatched_bracket(&self, expected: char) -> Option<TokenParseError> {
+        let found = match self.tokens.get(self.index)? {
+            Token::RightBrace => '}',
+            Token::RightBracket => ']',
+            _ => return None,
+        };
+        if found == expected {
+            return None;
+        }
+        Some(TokenParseError::MismatchedBracket {
+            expected,
+            found,
+            position: self.position(),
+        })
+    }
+
+    fn parse_value(&mut self) -> ParseResult {
+        self.parse_node()
+    }
+
+    /// Like [`Parser::parse_value`], building objects as lists of entries in source order
+    fn parse_ordered(&mut self) -> Result<OrderedValue, TokenParseError> {
+        self.parse_node()
+    }
+
+    fn parse_node<N: Node>(&mut self) -> Result<N, TokenParseError> {
+        match self.peek()? {
+            Token::LeftBrace => self.parse_object(),
+            Token::LeftBracket => self.parse_array(),
+            _ => self.parse_scalar().map(N::scalar),
+        }
+    }
+
+    /// Parses anything but an array or object, kept apart from the recursive
+    /// [`Parser::parse_node`] so its temporaries don't add to the stack of every nesting level
+    fn parse_scalar(&mut self) -> ParseResult {
+        let token = self.peek()?;
+
+        if matches!(
+            token,
+            Token::Null
+                | Token::False
+                | Token::True
+                | Token::Number(_)
+                | Token::Integer(_)
+                | Token::Custom(_)
+                | Token::String(_)
+        ) {
+            self.index += 1
+        }
+
+        match token {
+            Token::Null => Ok(Value::Null),
+            Token::False => Ok(Value::Boolean(false)),
+            Token::True => Ok(Value::Boolean(true)),
+            Token::Number(number) => Ok(Value::Number(*number)),
+            Token::Integer(integer) => Ok(Value::Integer(*integer)),
+            Token::Custom(value) => Ok(value.clone()),
+            Token::String(string) => self.parse_string(string, self.index - 1).map(Value::String),
+            // an empty slot, ex. `[1,,2]` or `[,]`
+            Token::Comma => Err(TokenParseError::UnexpectedComma { position: self.position() }),
+            _ => Err(TokenParseError::ExpectedValue)
+        }
+    }
+
+    fn parse_array<N: Node>(&mut self) -> Result<N, TokenParseError> {
+        let mut array = Vec::new();
+
+        loop {
+            self.index += 1;
+            if *self.peek()? == Token::RightBracket && (array.is_empty() || self.options.allow_trailing_commas) {
+                break;
+            }
+            if let Some(err) = self.mismatched_bracket(']') {
+                return Err(err);
+            }
+
+            if self.options.empty_slots_as_null && matches!(self.peek()?, Token::Comma | Token::RightBracket) {
+                array.push(N::scalar(Value::Null));
+            } else {
+                let value = self.parse_node().map_err(|err| err.in_value(&array.len().to_string()))?;
+                array.push(value);
+            }
+
+            match self.peek()? {
+                Token::RightBracket => break,
+                Token::Comma => {},
+                _ => return Err(self.mismatched_bracket(']').unwrap_or(TokenParseError::ExpectedComma)),
+            }
+        }
+
+        self.index += 1;
+
+        Ok(N::array(array))
+    }
+
+    fn parse_object<N: Node>(&mut self) -> Result<N, TokenParseError> {
+        let mut map = N::Entries::default();
+        loop {
+            // consume the previous LeftBrace or Comma token
+            self.index += 1;
+            if *self.peek()? == Token::RightBrace && (N::is_empty(&map) || self.options.allow_trailing_commas) {
+                break;
+            }
+            if let Some(err) = self.mismatched_bracket('}') {
+                return Err(err);
+            }
+
+            // identifiers never contain escapes, so they go through `parse_key` unchanged
+            if let Token::String(s) | Token::Identifier(s) = self.peek()? {
+                let key_position = self.position();
+                self.index += 1;
+                if Token::Colon == *self.peek()? {
+                    self.index += 1;
+                    let key = self.parse_key(s, self.index - 2)?;
+                    let value = self.parse_node().map_err(|err| err.in_value(&key))?;
+                    if N::contains_key(&map, &key) && self.options.reject_duplicate_keys {
+                        return Err(TokenParseError::DuplicateKey {
+                            key: key.to_string(),
+                            position: key_position,
+                        });
+                    }
+                    if N::contains_key(&map, &key) {
+                        self.diagnostics.push(Diagnostic::DuplicateKey {
+                            key: key.to_string(),
+                            position: key_position,
+                        });
+                    }
+                    N::insert(&mut map, key, value);
+                } else {
+                    return Err(TokenParseError::ExpectedColon);
+                }
+
+                match self.peek()? {
+                    Token::Comma => {}
+                    Token::RightBrace => break,
+                    _ => return Err(self.mismatched_bracket('}').unwrap_or(TokenParseError::ExpectedComma)),
+                }
+            } else if *self.peek()? == Token::Comma {
+                return Err(TokenParseError::UnexpectedComma { position: self.position() });
+            } else {
+                return Err(TokenParseError::ExpectedProperty);
+            }
+        }
+        // Consume the RightBrace token
+        self.index += 1;
+
+        Ok(N::object(map))
+    }
+}
+
+/// A tree [`Parser`] builds, so one set of container loops serves both [`Value`] and
+/// [`OrderedValue`]
+trait Node: Sized {
+    /// Collects the entries of an object as they're parsed
+    type Entries: Default;
+
+    fn scalar(value: Value) -> Self;
+    fn array(elements: Vec<Self>) -> Self;
+    fn object(entries: Self::Entries) -> Self;
+    fn is_empty(object: &Self::Entries) -> bool;
+    /// Whether inserting `key` would replace an earlier entry
+    fn contains_key(object: &Self::Entries, key: &str) -> bool;
+    fn insert(object: &mut Self::Entries, key: Arc<str>, value: Self);
+}
 
-    if matches!(
-        token,
-        Token::Null | Token::False | Token::True | Token::Number(_) | Token::String(_)
-    ) {
-        *index += 1
+impl Node for Value {
+    type Entries = BTreeMap<Arc<str>, Value>;
+
+    fn scalar(value: Value) -> Self {
+        value
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        Value::Array(elements)
+    }
+
+    fn object(entries: Self::Entries) -> Self {
+        Value::Object(entries)
+    }
+
+    fn is_empty(object: &Self::Entries) -> bool {
+        object.is_empty()
+    }
+
+    fn contains_key(object: &Self::Entries, key: &str) -> bool {
+        object.contains_key(key)
+    }
+
+    fn insert(object: &mut Self::Entries, key: Arc<str>, value: Self) {
+        object.insert(key, value);
+    }
+}
+
+/// Repeated keys are all kept, so none ever replaces another
+impl Node for OrderedValue {
+    type Entries = Vec<(String, OrderedValue)>;
+
+    fn scalar(value: Value) -> Self {
+        OrderedValue::Scalar(value)
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        OrderedValue::Array(elements)
     }
 
-    match token {
-        Token::Null => Ok(Value::Null),
-        Token::False => Ok(Value::Boolean(false)),
-        Token::True => Ok(Value::Boolean(true)),
-        Token::Number(number) => Ok(Value::Number(*number)),
-        Token::String(string) => parse_string(string),
-        Token::LeftBrace => parse_object(tokens, index),
-        Token::LeftBracket => parse_array(tokens, index),
-        _ => Err(TokenParseError::ExpectedValue)
+    fn object(entries: Self::Entries) -> Self {
+        OrderedValue::Object(entries)
+    }
+
+    fn is_empty(object: &Self::Entries) -> bool {
+        object.is_empty()
+    }
+
+    fn contains_key(_: &Self::Entries, _: &str) -> bool {
+        false
+    }
+
+    fn insert(object: &mut Self::Entries, key: Arc<str>, value: Self) {
+        object.push((String::from(&*key), value));
     }
 }
 
-fn parse_string(input: &str) -> ParseResult {
-    let mut output = String::new();
+fn parse_string(input: &str, options: &ParseOptions) -> Result<String, TokenParseError> {
+    // only a hint: escapes shrink when unescaped, but `InvalidEscapePolicy::Replace` can grow
+    // one, ex. `\q` into the 3 bytes of U+FFFD
+    let mut output = String::with_capacity(input.len());
+    unescape_with(input, &mut output, options)?;
+    Ok(output)
+}
+
+/// Appends the contents of a JSON string, given without its quotes, to `out` with escapes
+/// like `\n` and `\u00e9` decoded, so many strings can be unescaped into one reused buffer.
+/// Escapes JSON doesn't define are accepted like [`parse`] does, keeping the escaped character.
+/// On error `out` is left holding whatever came before the bad escape.
+pub fn unescape_into(input: &str, out: &mut String) -> Result<(), TokenParseError> {
+    unescape_with(input, out, &ParseOptions::default())
+}
+
+fn unescape_with(input: &str, output: &mut String, options: &ParseOptions) -> Result<(), TokenParseError> {
     let mut is_escaping = false;
     let mut chars = input.chars();
+    // byte offset of the backslash starting the current escape
+    let mut escape_start = 0;
 
     while let Some(next_char) = chars.next() {
         if is_escaping {
@@ -79,105 +880,83 @@ fn parse_string(input: &str) -> ParseResult {
                 // `\b` (backspace) is a valid escape in JSON, but not Rust
                 'b' => output.push('\u{8}'),
                 // `\f` (formfeed) is a valid escape in JSON, but not Rust
-                'f' => output.push('\u{12}'),
+                'f' => output.push('\u{c}'),
+                '/' => output.push('/'),
                 'n' => output.push('\n'),
                 'r' => output.push('\r'),
                 't' => output.push('\t'),
                 'u' => {
-                    let mut sum = 0;
-                    for i in 0..4 {
-                        let next_char = chars.next().ok_or(TokenParseError::UnfinishedEscape)?;
-                        let digit = next_char
-                            .to_digit(16)
-                            .ok_or(TokenParseError::InvalidHexValue)?;
-                        sum += (16u32).pow(3 - i) * digit;
+                    let mut code_point = parse_hex_escape(&mut chars)?;
+                    let invalid = TokenParseError::InvalidCodePointValue {
+                        value: code_point,
+                        position: escape_start,
+                    };
+                    // characters outside the Basic Multilingual Plane are escaped as a
+                    // UTF-16 surrogate pair, ex. `\uD83D\uDE00`. A high surrogate without its low
+                    // half, or a low surrogate on its own, can't be stored in a `String`.
+                    if (0xD800..0xDC00).contains(&code_point) {
+                        let mut pair = chars.clone();
+                        if pair.next() != Some('\\') || pair.next() != Some('u') {
+                            return Err(invalid);
+                        }
+                        let low = parse_hex_escape(&mut pair)?;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return Err(invalid);
+                        }
+                        chars = pair;
+                        code_point = 0x10000 + ((code_point - 0xD800) << 10) + (low - 0xDC00);
                     }
-                    let unescaped_char =
-                        char::from_u32(sum).ok_or(TokenParseError::InvalidCodePointValue)?;
-                    output.push(unescaped_char);
+                    output.push(char::from_u32(code_point).ok_or(invalid)?);
                 }
-                // any other character *may* be escaped, ex. `\q` just push that letter `q`
-                _ => output.push(next_char),
+                // JSON only defines the escapes above
+                _ if options.strict_escapes => return Err(TokenParseError::InvalidEscape(next_char)),
+                // any other character *may* be escaped, the policy decides what ones like `\q` become
+                _ => match options.invalid_escape_policy {
+                    InvalidEscapePolicy::KeepCharacter => output.push(next_char),
+                    InvalidEscapePolicy::KeepBackslash => {
+                        output.push('\\');
+                        output.push(next_char);
+                    }
+                    InvalidEscapePolicy::Replace(replacement) => output.push(replacement),
+                },
             }
             is_escaping = false;
         } else if next_char == '\\' {
             is_escaping = true;
+            escape_start = input.len() - chars.as_str().len() - 1;
         } else {
             output.push(next_char);
         }
     }
 
-    Ok(Value::String(output))
+    Ok(())
 }
 
-fn parse_array(tokens: &Vec<Token>, index: &mut usize) -> ParseResult {
-    let mut array = Vec::new();
-
-    loop {
-        *index += 1;
-        if tokens[*index] == Token::RightBracket {
-            break;
-        }
-
-        let value = parse_tokens(tokens, index)?;
-        array.push(value);
-
-        let token = &tokens[*index];
-        match token {
-            Token::RightBracket => break,
-            Token::Comma => {},
-            _ => return Err(TokenParseError::ExpectedComma),
-        }
+/// Reads the 4 hexadecimal digits following `\u`
+fn parse_hex_escape(chars: &mut core::str::Chars) -> Result<u32, TokenParseError> {
+    let mut sum = 0;
+    for i in 0..4 {
+        let next_char = chars.next().ok_or(TokenParseError::UnfinishedEscape)?;
+        let digit = next_char
+            .to_digit(16)
+            .ok_or(TokenParseError::InvalidHexValue)?;
+        sum += (16u32).pow(3 - i) * digit;
     }
-
-    *index += 1;
-
-    Ok(Value::Array(array))
-}
-
-fn parse_object(tokens: &Vec<Token>, index: &mut usize) -> ParseResult {
-    let mut map = HashMap::new();
-    loop {
-        // consume the previous LeftBrace or Comma token
-        *index += 1;
-        if tokens[*index] == Token::RightBrace {
-            break;
-        }
-
-        if let Token::String(s) = &tokens[*index] {
-            *index += 1;
-            if Token::Colon == tokens[*index] {
-                *index += 1;
-                let key = s.clone();
-                let value = parse_tokens(tokens, index)?;
-                map.insert(key, value);
-            } else {
-                return Err(TokenParseError::ExpectedColon);
-            }
-
-            match &tokens[*index] {
-                Token::Comma => {}
-                Token::RightBrace => break,
-                _ => return Err(TokenParseError::ExpectedComma),
-            }
-        } else {
-            return Err(TokenParseError::ExpectedProperty);
-        }
-    }
-    // Consume the RightBrace token
-    *index += 1;
-
-    Ok(Value::Object(map))
+    Ok(sum)
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
     use crate::tokenize::Token;
     use crate::Value;
 
     fn check(input: Vec<Token>, expected: Value) {
         let mut index = 0;
-        let value = super::parse_tokens(&input, &mut index).unwrap();
+        let value = super::parse_value_at(&input, &mut index).unwrap();
         assert_eq!(value, expected);
     }
 
@@ -272,11 +1051,699 @@ mod tests {
     fn test_parse() {
         let input = String::from(r#"{"key": "value"}"#);
         let expected = Value::Object(
-            vec![("key".to_string(), Value::String("value".to_string()))]
+            vec![("key".into(), Value::String("value".to_string()))]
                 .into_iter()
                 .collect(),
         );
 
-        assert_eq!(super::parse(input).unwrap(), expected);
+        assert_eq!(super::parse(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_or_null() {
+        assert_eq!(super::parse_or_null("").unwrap(), Value::Null);
+        assert_eq!(super::parse_or_null(" \n\t ").unwrap(), Value::Null);
+        assert_eq!(
+            super::parse_or_null(" [true] ").unwrap(),
+            Value::Array(vec![Value::Boolean(true)])
+        );
+    }
+
+    #[test]
+    fn test_require_top_level_container() {
+        use super::{parse_with_options, ParseOptions, TokenParseError};
+
+        let strict = ParseOptions {
+            require_top_level_container: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_with_options("42", &strict),
+            Err(TokenParseError::TopLevelMustBeContainer.into())
+        );
+        assert_eq!(
+            parse_with_options(r#""x""#, &strict),
+            Err(TokenParseError::TopLevelMustBeContainer.into())
+        );
+        assert_eq!(parse_with_options(" [42]", &strict).unwrap(), Value::Array(vec![Value::Number(42.0)]));
+        assert_eq!(parse_with_options("42", &ParseOptions::default()).unwrap(), Value::Number(42.0));
+    }
+
+    #[test]
+    fn test_mismatched_bracket() {
+        use super::{parse, TokenParseError};
+
+        let mismatch = |expected, found, position| TokenParseError::MismatchedBracket { expected, found, position };
+        let in_value = |path: &str, cause| TokenParseError::InValue { path: path.into(), cause: Box::new(cause) };
+        assert_eq!(parse("[1}"), Err(mismatch(']', '}', 2).into()));
+        assert_eq!(parse(r#"{"a":1]"#), Err(mismatch('}', ']', 6).into()));
+        assert_eq!(parse(r#"[{"a":1]"#), Err(in_value("/0", mismatch('}', ']', 7)).into()));
+        assert_eq!(parse("[}"), Err(mismatch(']', '}', 1).into()));
+        assert_eq!(parse(r#"{"é": [1, 2}}"#), Err(in_value("/é", mismatch(']', '}', 12)).into()));
+    }
+
+    #[test]
+    fn test_unexpected_eof() {
+        use super::{parse, TokenParseError};
+
+        assert_eq!(parse(""), Err(TokenParseError::UnexpectedEof.into()));
+        assert_eq!(parse("[1, 2"), Err(TokenParseError::UnexpectedEof.into()));
+        assert_eq!(
+            parse(r#"{"a":"#),
+            Err(TokenParseError::InValue {
+                path: "/a".into(),
+                cause: Box::new(TokenParseError::UnexpectedEof)
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn parses_string_surrogate_pair() {
+        let input = vec![Token::String(r#"\uD83D\uDE00 \u00e9"#.into())];
+        let expected = Value::String("😀 é".into());
+
+        check(input, expected);
+    }
+
+    #[test]
+    fn parses_object_key_escapes() {
+        let value = super::parse(r#"{"\uD83D\uDE00": 1, "a\"b": 2}"#).unwrap();
+
+        assert_eq!(value.get("😀"), Some(&Value::Number(1.0)));
+        assert_eq!(value.get(r#"a"b"#), Some(&Value::Number(2.0)));
+        assert_eq!(value.get(r#"\uD83D\uDE00"#), None);
+    }
+
+    #[test]
+    fn test_parse_with_stats() {
+        use super::{parse_with_stats, ParseStats};
+
+        let (value, stats) = parse_with_stats(r#" {"a": [1, "x", {"b": null}], "c": 2.5} "#).unwrap();
+        assert_eq!(value, super::parse(r#"{"a": [1, "x", {"b": null}], "c": 2.5}"#).unwrap());
+        assert_eq!(
+            stats,
+            ParseStats {
+                tokens: 19,
+                bytes_consumed: 39,
+                max_depth: 3,
+                strings: 1,
+                numbers: 2,
+                containers: 3,
+            }
+        );
+
+        let (_, stats) = parse_with_stats("true").unwrap();
+        assert_eq!(stats.max_depth, 0);
+        assert_eq!(stats.bytes_consumed, 4);
+    }
+
+    #[test]
+    fn test_expand_dotted_keys() {
+        use super::{parse, parse_with_options, ParseOptions, TokenParseError};
+
+        let options = ParseOptions {
+            expand_dotted_keys: true,
+            ..ParseOptions::default()
+        };
+        let expand = |input| parse_with_options(input, &options);
+
+        assert_eq!(expand(r#"{"a.b":1,"a.c":2}"#), parse(r#"{"a":{"b":1,"c":2}}"#));
+        assert_eq!(
+            expand(r#"[{"x": {"a.b.c": true}, "a": {"d": 1}, "a.e": null}]"#),
+            parse(r#"[{"x": {"a": {"b": {"c": true}}}, "a": {"d": 1, "e": null}}]"#)
+        );
+        assert_eq!(
+            expand(r#"{"a": 1, "a.b": 2}"#),
+            Err(TokenParseError::DottedKeyConflict("a.b".into()).into())
+        );
+        assert_eq!(
+            expand(r#"{"a": {"b": 1}, "a.b": 2}"#),
+            Err(TokenParseError::DottedKeyConflict("a.b".into()).into())
+        );
+        assert_eq!(parse(r#"{"a.b":1}"#).unwrap().get("a.b"), Some(&Value::Number(1.0)));
+    }
+
+    #[test]
+    fn test_capacity_hints() {
+        use super::{parse, parse_with_options, ParseOptions};
+        use crate::test_alloc::count_allocations;
+
+        let input = format!("[{}]", vec![r#"{"key": "some string value"}"#; 200].join(","));
+        let hinted = ParseOptions {
+            token_capacity: 1200,
+            string_capacity: 32,
+            ..ParseOptions::default()
+        };
+
+        let (value, plain_allocations) = count_allocations(|| parse(&input).unwrap());
+        let (hinted_value, hinted_allocations) = count_allocations(|| parse_with_options(&input, &hinted).unwrap());
+        assert_eq!(hinted_value, value);
+        assert!(
+            hinted_allocations < plain_allocations,
+            "{} allocations with hints, {} without",
+            hinted_allocations,
+            plain_allocations
+        );
+    }
+
+    #[test]
+    fn test_parse_with_diagnostics() {
+        use super::{parse_with_diagnostics, Diagnostic};
+
+        let (value, diagnostics) = parse_with_diagnostics(r#"{"a":1,"a":2}"#).unwrap();
+        assert_eq!(value.get("a"), Some(&Value::Number(2.0)));
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::DuplicateKey {
+                key: "a".into(),
+                position: 7,
+            }]
+        );
+
+        let (_, diagnostics) = parse_with_diagnostics(r#"[{"a":1}, {"a":2}]"#).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        use super::{parse, parse_with_options, ParseOptions, TokenParseError};
+
+        assert_eq!(
+            parse("[1,]"),
+            Err(TokenParseError::InValue {
+                path: "/1".into(),
+                cause: Box::new(TokenParseError::ExpectedValue)
+            }
+            .into())
+        );
+        assert_eq!(parse(r#"{"a":1,}"#), Err(TokenParseError::ExpectedProperty.into()));
+
+        let options = ParseOptions {
+            allow_trailing_commas: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(parse_with_options("[1,]", &options), parse("[1]"));
+        assert_eq!(parse_with_options(r#"{"a":[1,],}"#, &options), parse(r#"{"a":[1]}"#));
+        assert_eq!(
+            parse_with_options("[,]", &options),
+            Err(TokenParseError::InValue {
+                path: "/0".into(),
+                cause: Box::new(TokenParseError::UnexpectedComma { position: 1 })
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn test_json5_lite_profile() {
+        use super::{parse, parse_with_profile, ParseProfile};
+
+        let input = r#"
+            // service configuration
+            {
+                name: 'api "v2"',
+                $port: 8080, /* overridden in production */
+                hosts: ['a.example', 'b.example',],
+                'quoted key': {enabled: true, limit: null,},
+                "plain": "json",
+            }
+        "#;
+        let expected = parse(
+            r#"{
+                "name": "api \"v2\"",
+                "$port": 8080,
+                "hosts": ["a.example", "b.example"],
+                "quoted key": {"enabled": true, "limit": null},
+                "plain": "json"
+            }"#,
+        );
+        assert_eq!(parse_with_profile(input, ParseProfile::Json5Lite), expected);
+        assert!(parse_with_profile(input, ParseProfile::Json).is_err());
+        assert!(parse_with_profile("[0x10]", ParseProfile::Json5Lite).is_err());
+        assert!(parse_with_profile("{key: value}", ParseProfile::Json5Lite).is_err());
+    }
+
+    #[test]
+    fn test_strict_escapes() {
+        use super::{parse, parse_with_options, ParseOptions, TokenParseError};
+
+        let strict = ParseOptions {
+            strict_escapes: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_with_options(r#""a\qb""#, &strict),
+            Err(TokenParseError::InvalidEscape('q').into())
+        );
+        assert_eq!(
+            parse_with_options(r#"{"\x41": 1}"#, &strict),
+            Err(TokenParseError::InvalidEscape('x').into())
+        );
+        assert_eq!(parse(r#""a\qb""#).unwrap(), Value::String("aqb".into()));
+
+        let all_escapes = r#""\" \\ \/ \b \f \n \r \t \u0041""#;
+        assert_eq!(
+            parse_with_options(all_escapes, &strict).unwrap(),
+            Value::String("\" \\ / \u{8} \u{c} \n \r \t A".into())
+        );
+    }
+
+    #[test]
+    fn test_unpaired_surrogates() {
+        use super::{parse, parse_value_at, TokenParseError};
+
+        let invalid = |value, position| Err(TokenParseError::InvalidCodePointValue { value, position }.into());
+        let invalid_in = |path: &str, value, position| {
+            let cause = Box::new(TokenParseError::InvalidCodePointValue { value, position });
+            Err(TokenParseError::InValue { path: path.into(), cause }.into())
+        };
+
+        // a high surrogate followed by something other than `\u`
+        assert_eq!(parse(r#""ab\uD83Dx""#), invalid(0xD83D, 3));
+        assert_eq!(parse(r#""\uD83D""#), invalid(0xD83D, 1));
+        // a high surrogate followed by an escape that isn't a low surrogate
+        assert_eq!(parse(r#""\uD83D\u0041""#), invalid(0xD83D, 1));
+        // a low surrogate on its own, positioned within the document
+        assert_eq!(parse(r#"["ok", "a\uDC00"]"#), invalid_in("/1", 0xDC00, 9));
+        assert_eq!(parse(r#"{"\uDC00": 1}"#), invalid(0xDC00, 2));
+        // a valid pair
+        assert_eq!(parse(r#""\uD83D\uDE00""#).unwrap(), Value::String("\u{1F600}".into()));
+
+        // without source text the position is the string's token index
+        let tokens = [Token::LeftBracket, Token::String(r"\uDFFF".into()), Token::RightBracket];
+        assert_eq!(
+            parse_value_at(&tokens, &mut 0),
+            Err(TokenParseError::InValue {
+                path: "/0".into(),
+                cause: Box::new(TokenParseError::InvalidCodePointValue { value: 0xDFFF, position: 1 })
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_chunks() {
+        use super::{parse, parse_chunks, ParseError};
+
+        let input = r#"{"name": "Zoë ✓", "tags": ["日本", "🎉"]}"#;
+        let bytes = input.as_bytes();
+        let expected = parse(input).unwrap();
+
+        // every split point, many of which fall inside a multi-byte character
+        for split in 0..=bytes.len() {
+            let chunks = vec![bytes[..split].to_vec(), bytes[split..].to_vec()];
+            assert_eq!(parse_chunks(chunks).unwrap(), expected);
+        }
+        let single_bytes = bytes.iter().map(|&byte| vec![byte]);
+        assert_eq!(parse_chunks(single_bytes).unwrap(), expected);
+
+        assert_eq!(parse_chunks(vec![b"\"\xe6".to_vec(), b"\x97\"".to_vec()]), Err(ParseError::InvalidUtf8));
+    }
+
+    #[test]
+    fn test_scalar_fast_path() {
+        use super::{parse, parse_scalar, parse_with_options, ParseOptions};
+        use crate::test_alloc::count_allocations;
+
+        let scalars = [
+            "true", " null\n", "false", "42", "-0", "-2.5e-3", "1E+5", "01", "1.",
+            "9223372036854775808", "340282366920938463463374607431768211456", "\"short\"", "\"\"",
+        ];
+        for input in scalars {
+            assert!(parse_scalar(input).is_some(), "{}", input);
+            assert_eq!(parse(input), parse_with_options(input, &ParseOptions::default()), "{}", input);
+        }
+
+        let structural = [
+            "[1]", "{}", "\"a\\nb\"", "\"a\" \"b\"", "1 2", "1-2", "-", "1e", ".5", "+1", "nul", "\"", "",
+        ];
+        for input in structural {
+            assert_eq!(parse_scalar(input), None, "{}", input);
+            assert_eq!(parse(input), parse_with_options(input, &ParseOptions::default()), "{}", input);
+        }
+
+        assert_eq!(count_allocations(|| parse(" true ").unwrap()), (Value::Boolean(true), 0));
+        assert_eq!(count_allocations(|| parse("42").unwrap()), (Value::Number(42.0), 0));
+    }
+
+    #[test]
+    fn test_intern_keys() {
+        use super::{parse_with_options, Arc, ParseOptions};
+        use crate::test_alloc::count_allocations;
+
+        let records: Vec<String> = (0..1000)
+            .map(|i| format!(r#"{{"id": {}, "name": "n", "tag\u0073": []}}"#, i))
+            .collect();
+        let input = format!("[{}]", records.join(","));
+        let interned = ParseOptions {
+            intern_keys: true,
+            ..ParseOptions::default()
+        };
+
+        let (plain, plain_allocations) =
+            count_allocations(|| parse_with_options(&input, &ParseOptions::default()).unwrap());
+        let (value, interned_allocations) = count_allocations(|| parse_with_options(&input, &interned).unwrap());
+        assert_eq!(value, plain);
+        // three keys per record are allocated only once each, the escaped one also needs decoding
+        assert!(interned_allocations + 2900 < plain_allocations, "{} vs {}", interned_allocations, plain_allocations);
+
+        let Value::Array(records) = &value else { panic!("expected an array") };
+        let keys = |record: &Value| match record {
+            Value::Object(map) => map.keys().cloned().collect::<Vec<Arc<str>>>(),
+            other => panic!("unexpected {:?}", other),
+        };
+        for (first, last) in keys(&records[0]).iter().zip(keys(&records[999]).iter()) {
+            assert!(Arc::ptr_eq(first, last));
+        }
+        assert_eq!(records[999].get("id"), Some(&Value::Number(999.0)));
+        assert_eq!(records[999].get("tags"), Some(&Value::Array(vec![])));
+    }
+
+    #[test]
+    fn test_unexpected_comma() {
+        use super::{parse, TokenParseError};
+
+        let unexpected_comma = |position| Err(TokenParseError::UnexpectedComma { position }.into());
+        let empty_slot = |path: &str, position| {
+            let cause = Box::new(TokenParseError::UnexpectedComma { position });
+            Err(TokenParseError::InValue { path: path.into(), cause }.into())
+        };
+        assert_eq!(parse("[1,,2]"), empty_slot("/1", 3));
+        assert_eq!(parse("[,]"), empty_slot("/0", 1));
+        assert_eq!(parse("{,}"), unexpected_comma(1));
+        assert_eq!(parse(r#"{"a": 1, , "b": 2}"#), unexpected_comma(9));
+        assert_eq!(parse(r#"{"a": ,}"#), empty_slot("/a", 6));
+        assert_eq!(parse("[1 2]"), Err(TokenParseError::ExpectedComma.into()));
+    }
+
+    #[test]
+    fn test_number_suffix_handler() {
+        use super::{parse, parse_with_options, NumberSuffixHandler, ParseOptions};
+        use crate::TokenizeError;
+
+        fn units(raw: &str) -> Result<Value, TokenizeError> {
+            let split = raw.find(|c: char| c.is_alphabetic()).unwrap_or(raw.len());
+            let (number, unit) = raw.split_at(split);
+            let number: f64 = number.parse().map_err(TokenizeError::ParseNumberError)?;
+            match unit {
+                "s" => Ok(Value::Number(number)),
+                "MB" => Ok(Value::Number(number * 1024.0 * 1024.0)),
+                _ => Err(TokenizeError::CharNotRecognized(unit.chars().next().unwrap())),
+            }
+        }
+
+        let options = ParseOptions {
+            number_suffix_handler: Some(NumberSuffixHandler(units)),
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_with_options(r#"{"timeout": 10s, "size": -1.5MB, "n": 3}"#, &options),
+            parse(r#"{"timeout": 10, "size": -1572864, "n": 3}"#)
+        );
+        assert_eq!(parse_with_options("[2h]", &options), Err(TokenizeError::CharNotRecognized('h').into()));
+        assert_eq!(parse("[10s]"), Err(TokenizeError::CharNotRecognized('s').into()));
+    }
+
+    #[test]
+    fn test_parse_value_at() {
+        use super::parse_value_at;
+        use crate::tokenize;
+
+        let tokens = tokenize(r#"{"a": [1, {"b": null}], "c": true}"#).unwrap();
+        let mut index = 3;
+        assert_eq!(tokens[index], Token::LeftBracket);
+        let value = parse_value_at(&tokens, &mut index).unwrap();
+        assert_eq!(value, crate::parse(r#"[1, {"b": null}]"#).unwrap());
+        assert_eq!(tokens[index], Token::Comma);
+
+        index += 3;
+        assert_eq!(parse_value_at(&tokens, &mut index), Ok(Value::Boolean(true)));
+        assert_eq!(parse_value_at(&tokens, &mut index), Err(super::TokenParseError::ExpectedValue));
+    }
+
+    #[test]
+    fn test_integer_paths() {
+        use super::{parse, parse_with_options, ParseOptions, TokenParseError};
+
+        let options = ParseOptions {
+            integer_paths: vec!["/users/0/id".into(), "/count".into(), "/a~1b".into(), "/missing/0".into()],
+            ..ParseOptions::default()
+        };
+        let input = r#"{"users": [{"id": 3}, {"id": 4.5}], "count": 1e2, "a/b": 18446744073709551616}"#;
+        assert_eq!(parse_with_options(input, &options), parse(input));
+
+        assert_eq!(
+            parse_with_options(r#"{"users": [{"id": 3.5}], "count": 1}"#, &options),
+            Err(TokenParseError::ExpectedInteger("/users/0/id".into()).into())
+        );
+        assert_eq!(
+            parse_with_options(r#"{"count": "1"}"#, &options),
+            Err(TokenParseError::ExpectedInteger("/count".into()).into())
+        );
+    }
+
+    #[test]
+    fn test_max_tokens() {
+        use super::{parse, parse_with_options, ParseError, ParseOptions};
+
+        let options = ParseOptions {
+            max_tokens: Some(1000),
+            ..ParseOptions::default()
+        };
+        let huge = format!("[{}0]", "0,".repeat(100_000));
+        assert_eq!(parse_with_options(&huge, &options), Err(ParseError::BudgetExceeded));
+        assert!(parse(&huge).is_ok());
+
+        let normal = r#"{"a": [1, 2, 3], "b": {"c": null}}"#;
+        assert_eq!(parse_with_options(normal, &options), parse(normal));
+
+        // exactly at the budget is still fine
+        let exact = ParseOptions {
+            max_tokens: Some(5),
+            ..ParseOptions::default()
+        };
+        assert!(parse_with_options("[1, 2]", &exact).is_ok());
+        assert_eq!(parse_with_options("[1, 2, 3]", &exact), Err(ParseError::BudgetExceeded));
+    }
+
+    #[test]
+    fn test_error_path() {
+        use super::{parse, TokenParseError};
+
+        let input = r#"{"users": [{"name": "a", "age": 1}, {"name": "b"}, {"name": "c", "age": 3 4}]}"#;
+        assert_eq!(
+            parse(input),
+            Err(TokenParseError::InValue {
+                path: "/users/2".into(),
+                cause: Box::new(TokenParseError::ExpectedComma)
+            }
+            .into())
+        );
+
+        let input = r#"{"config": {"a~b/c": {"limits": [0, {"max": [}]}}}"#;
+        assert_eq!(
+            parse(input),
+            Err(TokenParseError::InValue {
+                path: "/config/a~0b~1c/limits/1/max".into(),
+                cause: Box::new(TokenParseError::MismatchedBracket {
+                    expected: ']',
+                    found: '}',
+                    position: 45
+                })
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn test_empty_slots_as_null() {
+        use super::{parse, parse_with_options, ParseOptions, TokenParseError};
+
+        let options = ParseOptions {
+            empty_slots_as_null: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(parse_with_options(r#"["a",,"b"]"#, &options), parse(r#"["a", null, "b"]"#));
+        assert_eq!(parse_with_options(r#"[, "a", ,]"#, &options), parse(r#"[null, "a", null, null]"#));
+        assert_eq!(parse_with_options("[[,], []]", &options), parse("[[null, null], []]"));
+
+        let trailing = ParseOptions {
+            allow_trailing_commas: true,
+            ..options.clone()
+        };
+        assert_eq!(parse_with_options(r#"["a",,]"#, &trailing), parse(r#"["a", null]"#));
+
+        assert_eq!(
+            parse(r#"["a",,"b"]"#),
+            Err(TokenParseError::InValue {
+                path: "/1".into(),
+                cause: Box::new(TokenParseError::UnexpectedComma { position: 5 })
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        use super::{parse, parse_strict, TokenParseError, TokenizeError};
+
+        let input = r#"{"a": [1, -0.5, 2e10, "tab\t"], "b": {"c": null}}"#;
+        assert_eq!(parse_strict(input), parse(input));
+
+        assert_eq!(
+            parse_strict(r#"{"a": {"b": 1, "b": 2}}"#),
+            Err(TokenParseError::InValue {
+                path: "/a".into(),
+                cause: Box::new(TokenParseError::DuplicateKey {
+                    key: "b".into(),
+                    position: 15
+                })
+            }
+            .into())
+        );
+        assert_eq!(parse_strict("[1] [2]"), Err(TokenParseError::TrailingData { position: 4 }.into()));
+        assert_eq!(parse_strict("[01]"), Err(TokenizeError::InvalidNumber("01".into()).into()));
+        assert_eq!(parse_strict("[1.]"), Err(TokenizeError::InvalidNumber("1.".into()).into()));
+        assert_eq!(parse_strict("\"a\tb\""), Err(TokenizeError::ControlCharacter('\t').into()));
+        assert!(parse_strict("NaN").is_err());
+        assert!(parse("[01] 2").is_ok());
+
+        assert_eq!(parse_strict(r#""\q""#), Err(TokenParseError::InvalidEscape('q').into()));
+        assert_eq!(parse_strict("\u{c}1"), Err(TokenizeError::CharNotRecognized('\u{c}').into()));
+        assert_eq!(parse_strict(" \t\r\n1"), Ok(Value::Number(1.0)));
+        assert_eq!(parse("\u{c}1"), Ok(Value::Number(1.0)));
+    }
+
+    #[test]
+    fn test_numbers_as_strings() {
+        use super::{parse_with_options, ParseError, ParseOptions};
+        use crate::{to_string, to_string_with_options, SerializeOptions, TokenizeError};
+
+        let options = ParseOptions {
+            numbers_as_strings: true,
+            ..ParseOptions::default()
+        };
+        let preserve = SerializeOptions {
+            preserve_number_text: true,
+            ..SerializeOptions::default()
+        };
+        for input in ["0.1", "123456789012345678901234567890123456789012345", "1e400", "-0.10E-007"] {
+            let value = parse_with_options(input, &options).unwrap();
+            assert_eq!(value, Value::RawNumber(input.into()));
+            assert_eq!(to_string_with_options(&value, &preserve), input);
+        }
+
+        let input = r#"{"a":[0.1,1e400,{"b":-0}],"c":100000000000000000000000000000000000000000}"#;
+        assert_eq!(to_string_with_options(&parse_with_options(input, &options).unwrap(), &preserve), input);
+        assert_eq!(Value::RawNumber("42".into()).as_i128(), Some(42));
+        assert_eq!(Value::RawNumber("1e400".into()).as_number_lossy(), Some(f64::INFINITY));
+
+        for input in ["-", "1e", "1e+", "-.", "--1", "[1, -]"] {
+            let err = parse_with_options(input, &options).unwrap_err();
+            assert!(matches!(err, ParseError::TokenizeError(TokenizeError::ParseNumberError(_))), "{}", input);
+        }
+        assert_eq!(to_string(&Value::RawNumber("1e".into())), "null");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unescape_into() {
+        use super::{unescape_into, TokenParseError};
+
+        let mut buffer = String::new();
+        for fragment in [r"tab\there", r"\u00e9\/", "", r"\uD83D\uDE00 \q"] {
+            unescape_into(fragment, &mut buffer).unwrap();
+        }
+        assert_eq!(buffer, "tab\there\u{e9}/\u{1F600} q");
+
+        buffer.clear();
+        assert_eq!(unescape_into(r"ok\u12", &mut buffer), Err(TokenParseError::UnfinishedEscape));
+        assert_eq!(buffer, "ok");
+    }
+
+    #[test]
+    fn test_lowercase_keys() {
+        use super::{parse, parse_with_options, ParseOptions, TokenParseError};
+
+        let options = ParseOptions {
+            lowercase_keys: true,
+            ..ParseOptions::default()
+        };
+        let input = r#"{"Name": "Ada", "NESTED": {"ÉTÉ": [{"Key": "Value"}]}}"#;
+        assert_eq!(
+            parse_with_options(input, &options),
+            parse(r#"{"name": "Ada", "nested": {"été": [{"key": "Value"}]}}"#)
+        );
+
+        let collision = r#"{"A": 1, "a": 2}"#;
+        assert_eq!(parse_with_options(collision, &options), parse(r#"{"a": 2}"#));
+
+        let strict = ParseOptions {
+            reject_duplicate_keys: true,
+            ..options
+        };
+        assert_eq!(
+            parse_with_options(collision, &strict),
+            Err(TokenParseError::DuplicateKey {
+                key: "a".into(),
+                position: 9
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn test_invalid_escape_policy() {
+        use super::{parse_with_options, InvalidEscapePolicy, ParseOptions};
+
+        let parse_with = |policy| {
+            let options = ParseOptions {
+                invalid_escape_policy: policy,
+                ..ParseOptions::default()
+            };
+            parse_with_options(r#"["a\qb\n"]"#, &options).unwrap()
+        };
+        let string = |s: &str| Value::Array(vec![Value::String(s.into())]);
+
+        assert_eq!(parse_with(InvalidEscapePolicy::KeepCharacter), string("aqb\n"));
+        assert_eq!(parse_with(InvalidEscapePolicy::KeepBackslash), string("a\\qb\n"));
+        assert_eq!(parse_with(InvalidEscapePolicy::Replace('\u{fffd}')), string("a\u{fffd}b\n"));
+    }
+
+    #[test]
+    fn test_decimal_separator() {
+        use super::{parse, parse_with_options, ParseOptions};
+
+        let options = ParseOptions {
+            decimal_separator: ',',
+            ..ParseOptions::default()
+        };
+        assert_eq!(parse_with_options("3,14", &options), parse("3.14"));
+        assert_eq!(parse_with_options("3.14", &options), parse("3.14"));
+        assert_eq!(parse_with_options("-0,5e2", &options), Ok(Value::Number(-50.0)));
+        assert_eq!(
+            parse_with_options(r#"{"a": 1,5, "b": [0,5,1, {"c": 2,25}], "d":3}"#, &options),
+            parse(r#"{"a": 1.5, "b": [0, 5, 1, {"c": 2.25}], "d": 3}"#)
+        );
+        assert_eq!(parse_with_options("[1,2]", &options), parse("[1, 2]"));
+        assert_ne!(parse_with_options("[1,2]", &options), parse("[1.2]"));
+        assert_ne!(parse("3,14"), parse("3.14"));
+    }
+
+    #[test]
+    fn test_leading_whitespace() {
+        use super::{parse, parse_strict};
+
+        for (input, expected) in [("{}", "{}"), (r#"["a"]"#, r#"["a"]"#), ("-1.5", "-1.5"), ("null", "null")] {
+            let expected = parse(expected).unwrap();
+            for prefix in [" ", "\t", "\n", "\r\n", " \t\n  "] {
+                let input = format!("{}{}", prefix, input);
+                assert_eq!(parse(&input), Ok(expected.clone()), "{:?}", input);
+                assert_eq!(parse_strict(&input), Ok(expected.clone()), "{:?}", input);
+            }
+        }
+
+        // a framing marker stripped off the front leaves whitespace the parser skips
+        let framed = "#42# \n{\"a\": 1}";
+        assert_eq!(parse(framed.trim_start_matches("#42#")), parse(r#"{"a": 1}"#));
+    }
+}
@@ -0,0 +1,470 @@
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::Value;
+
+/// One step of a dotted path like `a.b[0].c`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Why [`Value::try_get_path`] found no value. Segments are numbered from 0.
+#[derive(Debug, PartialEq)]
+pub enum PathError {
+    /// The path isn't valid dotted path syntax
+    InvalidSyntax,
+    /// The object reached at `segment` has no key `key`
+    KeyNotFound { segment: usize, key: String },
+    /// The array reached at `segment` is too short for `index`
+    IndexOutOfRange { segment: usize, index: usize },
+    /// `segment` is a key applied to a non-object or an index applied to a non-array
+    TypeMismatch { segment: usize },
+}
+
+/// Splits a dotted path into its segments, or `None` when the syntax is invalid.
+/// Keys are separated by `.` and array indices are written in brackets, ex. `users[2].name`.
+/// The empty path refers to the root value.
+pub(crate) fn parse_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Some(segments);
+    }
+
+    for (i, part) in path.split('.').enumerate() {
+        let (key, mut indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key));
+        } else if i > 0 || indices.is_empty() {
+            // only the first part may start straight away with an index, ex. `[0].a`
+            return None;
+        }
+
+        while !indices.is_empty() {
+            let close = indices.find(']')?;
+            let index = indices[1..close].parse().ok()?;
+            segments.push(PathSegment::Index(index));
+            indices = &indices[close + 1..];
+            if !indices.is_empty() && !indices.starts_with('[') {
+                return None;
+            }
+        }
+    }
+
+    Some(segments)
+}
+
+/// Splits a JSON Pointer like `/a/0/b` into its segments, undoing the `~0` and `~1` escapes.
+/// The empty pointer has no segments. Returns `None` for anything else not starting with `/`.
+fn decode_pointer_segments(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let segments = pointer.strip_prefix('/')?.split('/');
+    Some(segments.map(|segment| segment.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+/// Looks up the value a JSON Pointer like `/a/0/b` refers to. The empty pointer refers to
+/// `value` itself.
+pub(crate) fn resolve_pointer<'a>(value: &'a Value, pointer: &str) -> Option<&'a Value> {
+    decode_pointer_segments(pointer)?.iter().try_fold(value, |current, segment| match current {
+        Value::Object(map) => map.get(segment.as_str()),
+        Value::Array(array) => array.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Mutable counterpart of [`resolve_pointer`]
+fn resolve_pointer_mut<'a>(value: &'a mut Value, pointer: &str) -> Option<&'a mut Value> {
+    decode_pointer_segments(pointer)?.iter().try_fold(value, |current, segment| match current {
+        Value::Object(map) => map.get_mut(segment.as_str()),
+        Value::Array(array) => array.get_mut(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Appends `/segment` to a JSON Pointer, escaping `~` and `/` as RFC 6901 requires
+pub(crate) fn push_pointer_segment(pointer: &mut String, segment: &str) {
+    pointer.push('/');
+    for ch in segment.chars() {
+        match ch {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            c => pointer.push(c),
+        }
+    }
+}
+
+impl Value {
+    /// Looks up a nested value by a dotted path like `a.b[0].c`. Returns `None` when the path
+    /// is invalid or doesn't lead to a value.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        self.try_get_path(path).ok()
+    }
+
+    /// Like [`Value::get_path`], with a [`PathError`] saying where and why the lookup failed
+    pub fn try_get_path(&self, path: &str) -> Result<&Value, PathError> {
+        let mut current = self;
+        for (i, segment) in parse_path(path).ok_or(PathError::InvalidSyntax)?.into_iter().enumerate() {
+            current = match (segment, current) {
+                (PathSegment::Key(key), Value::Object(map)) => map.get(key).ok_or_else(|| PathError::KeyNotFound {
+                    segment: i,
+                    key: String::from(key),
+                })?,
+                (PathSegment::Index(index), Value::Array(array)) => array
+                    .get(index)
+                    .ok_or(PathError::IndexOutOfRange { segment: i, index })?,
+                _ => return Err(PathError::TypeMismatch { segment: i }),
+            };
+        }
+        Ok(current)
+    }
+
+    /// Mutable counterpart of [`Value::get_path`]. Missing values aren't created, the path
+    /// has to exist in full.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Value> {
+        self.get_segments_mut(&parse_path(path)?)
+    }
+
+    fn get_segments_mut(&mut self, segments: &[PathSegment]) -> Option<&mut Value> {
+        let mut current = self;
+        for segment in segments {
+            current = match (*segment, current) {
+                (PathSegment::Key(key), Value::Object(map)) => map.get_mut(key)?,
+                (PathSegment::Index(index), Value::Array(array)) => array.get_mut(index)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// Removes the object entry or array element a dotted path like `a.b[0]` refers to and
+    /// returns it, shifting later array elements down. Returns `None` and changes nothing when
+    /// the path is invalid, doesn't lead to a value, or is empty.
+    pub fn remove_path(&mut self, path: &str) -> Option<Value> {
+        let segments = parse_path(path)?;
+        let (last, parents) = segments.split_last()?;
+        match (*last, self.get_segments_mut(parents)?) {
+            (PathSegment::Key(key), Value::Object(map)) => map.remove(key),
+            (PathSegment::Index(index), Value::Array(array)) if index < array.len() => Some(array.remove(index)),
+            _ => None,
+        }
+    }
+
+    /// Looks up several JSON Pointers like `/a/0/b` at once, returning the results in the same
+    /// order as `pointers` with `None` for the ones that don't lead to a value.
+    pub fn get_many(&self, pointers: &[&str]) -> Vec<Option<&Value>> {
+        pointers.iter().map(|pointer| resolve_pointer(self, pointer)).collect()
+    }
+
+    /// Removes the object entry or array element a JSON Pointer like `/a/0` refers to and
+    /// returns it, shifting later array elements down. The empty pointer refers to the value
+    /// itself, which can't be removed from itself, so it returns `None` and changes nothing,
+    /// as does a pointer that doesn't lead to a value.
+    pub fn pointer_remove(&mut self, pointer: &str) -> Option<Value> {
+        let (parent, _) = pointer.rsplit_once('/')?;
+        let last = decode_pointer_segments(pointer)?.pop()?;
+        match resolve_pointer_mut(self, parent)? {
+            Value::Object(map) => map.remove(last.as_str()),
+            Value::Array(array) => {
+                let index = last.parse::<usize>().ok().filter(|index| *index < array.len())?;
+                Some(array.remove(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Iterates over every value in the document, containers included, paired with its
+    /// JSON Pointer. Parents come before their children, object keys in map order and array
+    /// elements by index. The root has the empty pointer.
+    pub fn walk(&self) -> impl Iterator<Item = (String, &Value)> {
+        let mut stack = Vec::from([(String::new(), self)]);
+        core::iter::from_fn(move || {
+            let (pointer, value) = stack.pop()?;
+            let child = |segment: &str| {
+                let mut child = pointer.clone();
+                push_pointer_segment(&mut child, segment);
+                child
+            };
+            // pushed in reverse so the first child is popped next
+            match value {
+                Value::Object(map) => stack.extend(map.iter().rev().map(|(key, value)| (child(key), value))),
+                Value::Array(array) => {
+                    stack.extend(array.iter().enumerate().rev().map(|(i, value)| (child(&i.to_string()), value)))
+                }
+                _ => {}
+            }
+            Some((pointer, value))
+        })
+    }
+
+    /// Iterates over every value depth first, the same as [`Value::walk`]: each container is
+    /// followed by everything inside it before its next sibling.
+    pub fn depth_first(&self) -> impl Iterator<Item = (String, &Value)> {
+        self.walk()
+    }
+
+    /// Iterates over every value breadth first, paired with its JSON Pointer: the root, then
+    /// its children, then their children and so on, each level in [`Value::walk`] order.
+    pub fn breadth_first(&self) -> impl Iterator<Item = (String, &Value)> {
+        let mut queue = VecDeque::from([(String::new(), self)]);
+        core::iter::from_fn(move || {
+            let (pointer, value) = queue.pop_front()?;
+            let child = |segment: &str| {
+                let mut child = pointer.clone();
+                push_pointer_segment(&mut child, segment);
+                child
+            };
+            match value {
+                Value::Object(map) => queue.extend(map.iter().map(|(key, value)| (child(key), value))),
+                Value::Array(array) => {
+                    queue.extend(array.iter().enumerate().map(|(i, value)| (child(&i.to_string()), value)))
+                }
+                _ => {}
+            }
+            Some((pointer, value))
+        })
+    }
+
+    /// Lists the JSON Pointer and length of every string value longer than `max_bytes` bytes
+    /// of UTF-8, in [`Value::walk`] order. Object keys aren't checked.
+    pub fn find_oversized_strings(&self, max_bytes: usize) -> Vec<(String, usize)> {
+        self.walk()
+            .filter_map(|(pointer, value)| match value {
+                Value::String(string) if string.len() > max_bytes => Some((pointer, string.len())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Lists every leaf of the document, scalars and empty containers, paired with its JSON
+    /// Pointer in [`Value::walk`] order. [`Value::from_flat_pairs`] builds the document back.
+    pub fn to_flat_pairs(&self) -> Vec<(String, Value)> {
+        self.walk()
+            .filter(|(_, value)| match value {
+                Value::Array(array) => array.is_empty(),
+                Value::Object(map) => map.is_empty(),
+                _ => true,
+            })
+            .map(|(pointer, value)| (pointer, value.clone()))
+            .collect()
+    }
+
+    /// Builds a document from JSON Pointer and value pairs like those of
+    /// [`Value::to_flat_pairs`], creating the containers along each pointer. A container is
+    /// created as an array when the segment below it is a number and as an object otherwise,
+    /// so objects with keys like `"0"` come back as arrays. Array elements skipped over are
+    /// filled with `null`, and a `null` a later pointer goes through is replaced by a container.
+    /// Returns `None` for a pointer that isn't valid or that goes through any other scalar, or
+    /// when there are no pairs.
+    pub fn from_flat_pairs<I: IntoIterator<Item = (String, Value)>>(pairs: I) -> Option<Value> {
+        fn container_for(segment: &str) -> Value {
+            match segment.parse::<usize>() {
+                Ok(_) => Value::Array(Vec::new()),
+                Err(_) => Value::Object(BTreeMap::new()),
+            }
+        }
+
+        let mut root = None;
+        for (pointer, value) in pairs {
+            let segments = decode_pointer_segments(&pointer)?;
+            let Some(first) = segments.first() else {
+                root = Some(value);
+                continue;
+            };
+
+            let mut current = root.get_or_insert_with(|| container_for(first));
+            for (i, segment) in segments.iter().enumerate() {
+                if *current == Value::Null {
+                    *current = container_for(segment);
+                }
+                current = match current {
+                    Value::Object(map) => map.entry(Arc::from(segment.as_str())).or_insert(Value::Null),
+                    Value::Array(array) => {
+                        let index = segment.parse::<usize>().ok()?;
+                        if array.len() <= index {
+                            array.resize(index + 1, Value::Null);
+                        }
+                        &mut array[index]
+                    }
+                    _ => return None,
+                };
+                if i == segments.len() - 1 {
+                    *current = value.clone();
+                }
+            }
+        }
+        root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_parse_path() {
+        use PathSegment::*;
+
+        assert_eq!(parse_path("a.b[0].c"), Some(vec![Key("a"), Key("b"), Index(0), Key("c")]));
+        assert_eq!(parse_path("[1][2].x"), Some(vec![Index(1), Index(2), Key("x")]));
+        assert_eq!(parse_path(""), Some(vec![]));
+        assert_eq!(parse_path("a..b"), None);
+        assert_eq!(parse_path("a.[0]"), None);
+        assert_eq!(parse_path("a[x]"), None);
+        assert_eq!(parse_path("a[0"), None);
+        assert_eq!(parse_path("a[0]b"), None);
+    }
+
+    #[test]
+    fn test_get_path() {
+        let value = parse(r#"{"a": {"b": [{"c": 1}, {"c": 2}]}}"#).unwrap();
+        assert_eq!(value.get_path("a.b[1].c"), Some(&Value::Number(2.0)));
+        assert_eq!(value.get_path(""), Some(&value));
+        assert_eq!(value.get_path("a.b[2].c"), None);
+        assert_eq!(value.get_path("a[0]"), None);
+    }
+
+    #[test]
+    fn test_try_get_path() {
+        let value = parse(r#"{"a": {"b": [{"c": 1}, {"c": 2}]}}"#).unwrap();
+        assert_eq!(value.try_get_path("a.b[1].c"), Ok(&Value::Number(2.0)));
+        assert_eq!(value.try_get_path("a..b"), Err(PathError::InvalidSyntax));
+        assert_eq!(
+            value.try_get_path("a.x.c"),
+            Err(PathError::KeyNotFound {
+                segment: 1,
+                key: "x".into()
+            })
+        );
+        assert_eq!(
+            value.try_get_path("a.b[2].c"),
+            Err(PathError::IndexOutOfRange { segment: 2, index: 2 })
+        );
+        assert_eq!(value.try_get_path("a.b.c"), Err(PathError::TypeMismatch { segment: 2 }));
+        assert_eq!(value.try_get_path("[0]"), Err(PathError::TypeMismatch { segment: 0 }));
+    }
+
+    #[test]
+    fn test_get_path_mut() {
+        let mut value = parse(r#"{"a": {"b": [{"c": 1}, {"c": 2}]}}"#).unwrap();
+        *value.get_path_mut("a.b[0].c").unwrap() = Value::Boolean(true);
+        assert_eq!(value, parse(r#"{"a": {"b": [{"c": true}, {"c": 2}]}}"#).unwrap());
+
+        assert_eq!(value.get_path_mut("a.b[0].d"), None);
+        assert_eq!(value.get_path_mut("a.x.c"), None);
+        assert_eq!(value.get_path_mut("a.b.c"), None);
+    }
+
+    #[test]
+    fn test_remove_path() {
+        let mut value = parse(r#"{"a": {"b": {"c": 1, "d": 2}}, "items": ["x", "y", "z"]}"#).unwrap();
+        assert_eq!(value.remove_path("a.b.c"), Some(Value::Number(1.0)));
+        assert_eq!(value.remove_path("items[1]"), Some(Value::String("y".into())));
+        assert_eq!(value, parse(r#"{"a": {"b": {"d": 2}}, "items": ["x", "z"]}"#).unwrap());
+
+        assert_eq!(value.remove_path("a.b.c"), None);
+        assert_eq!(value.remove_path("items[2]"), None);
+        assert_eq!(value.remove_path("items.x"), None);
+        assert_eq!(value.remove_path("a..b"), None);
+        assert_eq!(value.remove_path(""), None);
+        assert_eq!(value.remove_path("[0]"), None);
+    }
+
+    #[test]
+    fn test_walk() {
+        let value = parse(r#"{"b": [1, {"c/d": null}], "a": {}, "e": "x"}"#).unwrap();
+        let paths: Vec<String> = value.walk().map(|(pointer, _)| pointer).collect();
+        assert_eq!(paths, ["", "/a", "/b", "/b/0", "/b/1", "/b/1/c~1d", "/e"]);
+
+        let (_, nested) = value.walk().nth(4).unwrap();
+        assert_eq!(nested, value.get_path("b[1]").unwrap());
+        assert_eq!(Value::Null.walk().collect::<Vec<_>>(), [(String::new(), &Value::Null)]);
+    }
+
+    #[test]
+    fn test_depth_and_breadth_first() {
+        let value = parse(r#"{"a": [1, [2]], "b": {"c": 3}}"#).unwrap();
+        let depth: Vec<String> = value.depth_first().map(|(pointer, _)| pointer).collect();
+        let breadth: Vec<String> = value.breadth_first().map(|(pointer, _)| pointer).collect();
+        assert_eq!(depth, ["", "/a", "/a/0", "/a/1", "/a/1/0", "/b", "/b/c"]);
+        assert_eq!(breadth, ["", "/a", "/b", "/a/0", "/a/1", "/b/c", "/a/1/0"]);
+
+        let mut depth_nodes: Vec<_> = value.depth_first().collect();
+        let mut breadth_nodes: Vec<_> = value.breadth_first().collect();
+        depth_nodes.sort_by(|a, b| a.0.cmp(&b.0));
+        breadth_nodes.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(depth_nodes, breadth_nodes);
+        assert_eq!(breadth_nodes[3], (String::from("/a/1"), value.get_path("a[1]").unwrap()));
+
+        let deep = parse(&format!("{}{}", "[".repeat(1_000), "]".repeat(1_000))).unwrap();
+        assert_eq!(deep.depth_first().count(), 1_000);
+        assert_eq!(deep.breadth_first().count(), 1_000);
+    }
+
+    #[test]
+    fn test_get_many() {
+        let value = parse(r#"{"a": {"b": [10, 20]}, "c~d": "x"}"#).unwrap();
+        assert_eq!(
+            value.get_many(&["/c~0d", "/a/x", "/a/b/1"]),
+            [Some(&Value::String("x".into())), None, Some(&Value::Number(20.0))]
+        );
+        assert_eq!(value.get_many(&["", "a"]), [Some(&value), None]);
+        assert!(value.get_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_pointer_remove() {
+        let mut value = parse(r#"{"a": {"b": 1, "c/d": 2}, "e": [10, 20, 30]}"#).unwrap();
+        assert_eq!(value.pointer_remove("/a/c~1d"), Some(Value::Number(2.0)));
+        assert_eq!(value.pointer_remove("/e/1"), Some(Value::Number(20.0)));
+        assert_eq!(value, parse(r#"{"a": {"b": 1}, "e": [10, 30]}"#).unwrap());
+
+        assert_eq!(value.pointer_remove(""), None);
+        assert_eq!(value.pointer_remove("/e/2"), None);
+        assert_eq!(value.pointer_remove("/a/x"), None);
+        assert_eq!(value.pointer_remove("/a/b/c"), None);
+        assert_eq!(value.pointer_remove("a"), None);
+        assert_eq!(value.pointer_remove("/a"), Some(parse(r#"{"b": 1}"#).unwrap()));
+        assert_eq!(value, parse(r#"{"e": [10, 30]}"#).unwrap());
+    }
+
+    #[test]
+    fn test_find_oversized_strings() {
+        let value = parse(r#"{"name": "ok", "bio": ["short", "naïve café"], "long key that is fine": 1}"#).unwrap();
+        assert_eq!(value.find_oversized_strings(10), [(String::from("/bio/1"), 12)]);
+        assert_eq!(value.find_oversized_strings(12), []);
+        assert_eq!(value.find_oversized_strings(0).len(), 3);
+    }
+
+    #[test]
+    fn test_flat_pairs() {
+        let value = parse(r#"{"a": {"b": [1, {"c/d": null}, []]}, "e": "x", "f": {}, "g": [[true]]}"#).unwrap();
+        let pairs = value.to_flat_pairs();
+        assert_eq!(
+            pairs.iter().map(|(pointer, _)| pointer.as_str()).collect::<Vec<_>>(),
+            ["/a/b/0", "/a/b/1/c~1d", "/a/b/2", "/e", "/f", "/g/0/0"]
+        );
+        assert_eq!(pairs[3].1, Value::String("x".into()));
+        assert_eq!(Value::from_flat_pairs(pairs), Some(value));
+
+        for scalar in [Value::Null, Value::Number(1.5), parse("[]").unwrap()] {
+            assert_eq!(Value::from_flat_pairs(scalar.to_flat_pairs()), Some(scalar));
+        }
+    }
+
+    #[test]
+    fn test_from_flat_pairs() {
+        let pairs = [(String::from("/a/2"), Value::Boolean(true)), (String::from("/b"), Value::Null)];
+        assert_eq!(Value::from_flat_pairs(pairs), Some(parse(r#"{"a": [null, null, true], "b": null}"#).unwrap()));
+
+        let through_scalar = [(String::from("/a"), Value::Number(1.0)), (String::from("/a/b"), Value::Null)];
+        assert_eq!(Value::from_flat_pairs(through_scalar), None);
+        assert_eq!(Value::from_flat_pairs([(String::from("a"), Value::Null)]), None);
+        assert_eq!(Value::from_flat_pairs([]), None);
+    }
+}
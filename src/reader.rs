@@ -0,0 +1,220 @@
+use std::io::{ErrorKind, Read};
+use std::vec;
+use std::vec::Vec;
+
+use crate::parse::{parse, ParseError};
+use crate::Value;
+
+/// Reads successive whitespace separated JSON values from `reader`, like a log file with one
+/// document per line.
+///
+/// Input is read in chunks and each value is parsed as soon as its last byte arrives, so a
+/// value may span any number of reads. Iteration stops after the first error.
+pub fn iter_reader<R: Read>(reader: R) -> impl Iterator<Item = Result<Value, ParseError>> {
+    iter_reader_with_buffer_size(reader, DEFAULT_BUFFER_SIZE)
+}
+
+/// Size of each read made by [`iter_reader`]
+const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Like [`iter_reader`], reading at most `buffer_size` bytes at a time. Larger reads can speed
+/// up big inputs, smaller ones save memory. Values come out the same either way.
+///
+/// # Panics
+///
+/// Panics if `buffer_size` is 0.
+pub fn iter_reader_with_buffer_size<R: Read>(
+    reader: R,
+    buffer_size: usize,
+) -> impl Iterator<Item = Result<Value, ParseError>> {
+    assert!(buffer_size > 0, "the reader buffer size must be at least 1 byte");
+    ReaderValues {
+        reader,
+        chunk: vec![0; buffer_size],
+        buffer: Vec::new(),
+        scanner: Scanner::default(),
+        eof: false,
+        finished: false,
+    }
+}
+
+struct ReaderValues<R> {
+    reader: R,
+    /// Space for a single read
+    chunk: Vec<u8>,
+    buffer: Vec<u8>,
+    scanner: Scanner,
+    eof: bool,
+    finished: bool,
+}
+
+/// Finds where a value ends in the raw bytes, keeping its place between reads.
+/// Multi-byte UTF-8 sequences never contain ASCII bytes, so they can be stepped over blindly.
+#[derive(Default)]
+struct Scanner {
+    position: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl Scanner {
+    /// Index just past the end of the value at the start of `bytes`, if it's complete
+    fn find_end(&mut self, bytes: &[u8]) -> Option<usize> {
+        while let Some(&byte) = bytes.get(self.position) {
+            self.position += 1;
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => {
+                        self.in_string = false;
+                        if self.depth == 0 {
+                            return Some(self.position);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.depth == 0 {
+                        return Some(self.position);
+                    }
+                }
+                // the end of a top-level scalar like `42` or `true`
+                b' ' | b'\t' | b'\n' | b'\r' if self.depth == 0 => return Some(self.position - 1),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+impl<R: Read> ReaderValues<R> {
+    /// Reads another chunk onto the buffer, flagging `eof` once the reader is exhausted
+    fn fill(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.reader.read(&mut self.chunk) {
+                Ok(0) => self.eof = true,
+                Ok(len) => self.buffer.extend_from_slice(&self.chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(ParseError::Io(e.kind())),
+            }
+            return Ok(());
+        }
+    }
+
+    fn next_value(&mut self) -> Result<Option<Value>, ParseError> {
+        let end = loop {
+            if self.scanner.position == 0 {
+                let whitespace = self.buffer.iter().take_while(|byte| byte.is_ascii_whitespace()).count();
+                self.buffer.drain(..whitespace);
+            }
+
+            if let Some(end) = self.scanner.find_end(&self.buffer) {
+                break end;
+            }
+            if self.eof {
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                // incomplete values are handed to the parser, which reports what's missing
+                break self.buffer.len();
+            }
+            self.fill()?;
+        };
+
+        self.scanner = Scanner::default();
+        let bytes: Vec<u8> = self.buffer.drain(..end).collect();
+        let text = core::str::from_utf8(&bytes).map_err(|_| ParseError::InvalidUtf8)?;
+        parse(text).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for ReaderValues<R> {
+    type Item = Result<Value, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.next_value();
+        if !matches!(result, Ok(Some(_))) {
+            self.finished = true;
+        }
+        result.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::io;
+
+    /// Hands out one chunk per read
+    struct ChunkedReader(VecDeque<&'static [u8]>);
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some(chunk) = self.0.pop_front() else {
+                return Ok(0);
+            };
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    fn chunked(chunks: &[&'static [u8]]) -> ChunkedReader {
+        ChunkedReader(chunks.iter().copied().collect())
+    }
+
+    #[test]
+    fn test_iter_reader_chunk_boundaries() {
+        // splits land inside a key, inside a multi-byte `é`, and inside the second value
+        let reader = chunked(&[b"{\"na", b"me\": \"caf\xc3", b"\xa9\"}\n[1, ", b"2]", b"\n 42"]);
+        let values: Vec<_> = iter_reader(reader).collect();
+        assert_eq!(
+            values,
+            [
+                parse(r#"{"name": "café"}"#),
+                parse("[1, 2]"),
+                Ok(Value::Number(42.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iter_reader_errors() {
+        let mut values = iter_reader(chunked(&[b"[1] \"\\\"}\" [2", b", 3"]));
+        assert_eq!(values.next(), Some(parse("[1]")));
+        assert_eq!(values.next(), Some(Ok(Value::String("\"}".into()))));
+        assert_eq!(values.next(), Some(Err(crate::TokenParseError::UnexpectedEof.into())));
+        assert_eq!(values.next(), None);
+
+        let mut values = iter_reader(chunked(&[b"\"\xff\""]));
+        assert_eq!(values.next(), Some(Err(ParseError::InvalidUtf8)));
+        assert_eq!(values.next(), None);
+
+        assert_eq!(iter_reader(chunked(&[b"  \n"])).next(), None);
+    }
+
+    #[test]
+    fn test_buffer_size() {
+        let input = r#"{"key": "value with \"quotes\" and ü"} [1, 2.5, [true]] null "x""#;
+        let collect = |buffer_size| iter_reader_with_buffer_size(input.as_bytes(), buffer_size).collect::<Vec<_>>();
+
+        let large = collect(1 << 16);
+        assert_eq!(large.len(), 4);
+        assert!(large.iter().all(Result::is_ok));
+        assert_eq!(collect(1), large);
+        assert_eq!(collect(3), large);
+    }
+}
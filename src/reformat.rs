@@ -0,0 +1,102 @@
+use alloc::string::String;
+
+use crate::parse::{ParseError, ParseOptions};
+use crate::tokenize::{tokenize_with_spans, Token};
+
+/// Strips all insignificant whitespace from JSON text without building a tree.
+///
+/// Every token is copied from the source as-is, so string contents and number text are kept
+/// exactly. The input is only tokenized, not checked against the JSON grammar.
+pub fn minify(input: &str) -> Result<String, ParseError> {
+    let (_, spans) = tokenize_with_spans(input, &ParseOptions::default())?;
+
+    let mut output = String::with_capacity(input.len());
+    for span in spans {
+        output.push_str(&input[span.start..span.end]);
+    }
+    Ok(output)
+}
+
+/// Re-indents JSON text without building a tree, using `indent` spaces per nesting level.
+///
+/// Like [`minify`], tokens are copied from the source as-is so numbers never go through a
+/// float round-trip, and the input is only tokenized, not checked against the JSON grammar.
+pub fn prettify(input: &str, indent: usize) -> Result<String, ParseError> {
+    let (tokens, spans) = tokenize_with_spans(input, &ParseOptions::default())?;
+
+    let mut output = String::with_capacity(input.len() * 2);
+    let mut depth: usize = 0;
+    for (i, (token, span)) in tokens.iter().zip(&spans).enumerate() {
+        let text = &input[span.start..span.end];
+        match token {
+            Token::LeftBrace | Token::LeftBracket => {
+                output.push_str(text);
+                depth += 1;
+                // empty containers stay on one line
+                if !matches!(tokens.get(i + 1), Some(Token::RightBrace | Token::RightBracket)) {
+                    push_newline(&mut output, indent * depth);
+                }
+            }
+            Token::RightBrace | Token::RightBracket => {
+                depth = depth.saturating_sub(1);
+                if i > 0 && !matches!(tokens[i - 1], Token::LeftBrace | Token::LeftBracket) {
+                    push_newline(&mut output, indent * depth);
+                }
+                output.push_str(text);
+            }
+            Token::Comma => {
+                output.push(',');
+                push_newline(&mut output, indent * depth);
+            }
+            Token::Colon => output.push_str(": "),
+            _ => output.push_str(text),
+        }
+    }
+    Ok(output)
+}
+
+fn push_newline(output: &mut String, width: usize) {
+    output.push('\n');
+    output.extend(core::iter::repeat_n(' ', width));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, TokenizeError};
+
+    #[test]
+    fn test_minify() {
+        let input = "{\n  \"a b\": [1.50, -0, 1e3],\n  \"c\": { \"d\" : \"x \\\" y\" },\n  \"e\": null\n}\n";
+        let minified = minify(input).unwrap();
+        assert_eq!(minified, r#"{"a b":[1.50,-0,1e3],"c":{"d":"x \" y"},"e":null}"#);
+        assert_eq!(parse(&minified).unwrap(), parse(input).unwrap());
+    }
+
+    #[test]
+    fn test_minify_invalid() {
+        assert_eq!(minify("[1, @]"), Err(TokenizeError::CharNotRecognized('@').into()));
+    }
+
+    #[test]
+    fn test_prettify() {
+        let input = r#"{"a":[1.50,{},[]],"b":{"c":1e400,"d":"x\ty"}}"#;
+        let expected = concat!(
+            "{\n",
+            "    \"a\": [\n",
+            "        1.50,\n",
+            "        {},\n",
+            "        []\n",
+            "    ],\n",
+            "    \"b\": {\n",
+            "        \"c\": 1e400,\n",
+            "        \"d\": \"x\\ty\"\n",
+            "    }\n",
+            "}",
+        );
+        let prettified = prettify(input, 4).unwrap();
+        assert_eq!(prettified, expected);
+        assert_eq!(parse(&prettified).unwrap(), parse(input).unwrap());
+        assert_eq!(minify(&prettified).unwrap(), input);
+    }
+}
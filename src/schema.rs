@@ -0,0 +1,196 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Value;
+
+/// A structural description of JSON documents: which types appear where, and which object
+/// keys are always present. Inferred from sample documents with [`Value::infer_schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    Null,
+    Boolean,
+    /// Any number, however it is stored
+    Number,
+    String,
+    /// An array whose elements all match the element schema, or `None` when only empty
+    /// arrays have been seen and nothing is known about the elements
+    Array(Option<Box<Schema>>),
+    Object {
+        properties: BTreeMap<String, Schema>,
+        /// Keys present in every sample
+        required: BTreeSet<String>,
+    },
+    /// Any one of several schemas, for values whose type differs between samples.
+    /// Never nested, and holds at most one schema of each type.
+    OneOf(Vec<Schema>),
+}
+
+impl Schema {
+    /// Combines the schemas of two samples into one that both samples are valid against.
+    /// Object keys missing from either side stop being required.
+    pub fn merge(self, other: Schema) -> Schema {
+        match (self, other) {
+            (Schema::Array(a), Schema::Array(b)) => Schema::Array(match (a, b) {
+                (Some(a), Some(b)) => Some(Box::new(a.merge(*b))),
+                (a, b) => a.or(b),
+            }),
+            (
+                Schema::Object { properties: mut a, required: a_required },
+                Schema::Object { properties: b, required: b_required },
+            ) => {
+                for (key, b_schema) in b {
+                    let schema = match a.remove(&key) {
+                        Some(a_schema) => a_schema.merge(b_schema),
+                        None => b_schema,
+                    };
+                    a.insert(key, schema);
+                }
+                Schema::Object {
+                    properties: a,
+                    required: a_required.intersection(&b_required).cloned().collect(),
+                }
+            }
+            (a, b) if a == b => a,
+            (a, b) => {
+                let mut schemas = a.into_alternatives();
+                schemas.extend(b.into_alternatives());
+                Schema::one_of(schemas)
+            }
+        }
+    }
+
+    fn into_alternatives(self) -> Vec<Schema> {
+        match self {
+            Schema::OneOf(schemas) => schemas,
+            schema => Vec::from([schema]),
+        }
+    }
+
+    /// Builds a `OneOf`, merging the alternatives that share a type
+    fn one_of(schemas: Vec<Schema>) -> Schema {
+        let mut merged: Vec<Schema> = Vec::new();
+        for schema in schemas {
+            let same_type = merged
+                .iter()
+                .position(|existing| core::mem::discriminant(existing) == core::mem::discriminant(&schema));
+            match same_type {
+                Some(i) => {
+                    let existing = merged.remove(i);
+                    merged.insert(i, existing.merge(schema));
+                }
+                None => merged.push(schema),
+            }
+        }
+        if merged.len() == 1 {
+            merged.remove(0)
+        } else {
+            Schema::OneOf(merged)
+        }
+    }
+
+    /// Checks `value` against this schema. Object keys the schema doesn't mention are allowed.
+    pub fn is_valid(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Schema::OneOf(schemas), value) => schemas.iter().any(|schema| schema.is_valid(value)),
+            (Schema::Null, Value::Null)
+            | (Schema::Boolean, Value::Boolean(_))
+            | (Schema::Number, Value::Number(_) | Value::Integer(_) | Value::RawNumber(_))
+            | (Schema::String, Value::String(_)) => true,
+            (Schema::Array(elements), Value::Array(array)) => match elements {
+                Some(elements) => array.iter().all(|value| elements.is_valid(value)),
+                None => true,
+            },
+            (Schema::Object { properties, required }, Value::Object(map)) => {
+                required.iter().all(|key| map.contains_key(key.as_str()))
+                    && map.iter().all(|(key, value)| {
+                        properties.get(&**key).is_none_or(|schema| schema.is_valid(value))
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    /// Infers a best-effort [`Schema`] from this sample document. Every key of an object is
+    /// required, and array elements of differing types give a [`Schema::OneOf`]. Schemas of
+    /// further samples can be combined with [`Schema::merge`].
+    pub fn infer_schema(&self) -> Schema {
+        match self {
+            Value::Null => Schema::Null,
+            Value::Boolean(_) => Schema::Boolean,
+            Value::Number(_) | Value::Integer(_) | Value::RawNumber(_) => Schema::Number,
+            Value::String(_) => Schema::String,
+            Value::Array(array) => Schema::Array(
+                array
+                    .iter()
+                    .map(Value::infer_schema)
+                    .reduce(Schema::merge)
+                    .map(Box::new),
+            ),
+            Value::Object(map) => Schema::Object {
+                properties: map.iter().map(|(key, value)| (String::from(&**key), value.infer_schema())).collect(),
+                required: map.keys().map(|key| String::from(&**key)).collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn object(properties: &[(&str, Schema)], required: &[&str]) -> Schema {
+        Schema::Object {
+            properties: properties.iter().map(|(key, schema)| (String::from(*key), schema.clone())).collect(),
+            required: required.iter().map(|key| String::from(*key)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_infer_schema() {
+        let sample = parse(r#"{"id": 1, "name": "a", "tags": ["x", 2, "y"], "meta": {"ok": true}, "none": []}"#);
+        let sample = sample.unwrap();
+        let schema = sample.infer_schema();
+
+        let expected = object(
+            &[
+                ("id", Schema::Number),
+                ("name", Schema::String),
+                ("tags", Schema::Array(Some(Box::new(Schema::OneOf(vec![Schema::String, Schema::Number]))))),
+                ("meta", object(&[("ok", Schema::Boolean)], &["ok"])),
+                ("none", Schema::Array(None)),
+            ],
+            &["id", "meta", "name", "none", "tags"],
+        );
+        assert_eq!(schema, expected);
+        assert!(schema.is_valid(&sample));
+        let wrong_type = parse(r#"{"id": "1", "name": "a", "tags": [], "meta": {"ok": true}, "none": []}"#);
+        assert!(!schema.is_valid(&wrong_type.unwrap()));
+        assert!(!schema.is_valid(&parse(r#"{"id": 1}"#).unwrap()));
+    }
+
+    #[test]
+    fn test_merge_schemas() {
+        let a = parse(r#"{"id": 1, "email": "a@b", "scores": []}"#).unwrap();
+        let b = parse(r#"{"id": 2, "email": null, "scores": [1.5], "extra": {}}"#).unwrap();
+        let schema = a.infer_schema().merge(b.infer_schema());
+
+        let expected = object(
+            &[
+                ("id", Schema::Number),
+                ("email", Schema::OneOf(vec![Schema::String, Schema::Null])),
+                ("scores", Schema::Array(Some(Box::new(Schema::Number)))),
+                ("extra", object(&[], &[])),
+            ],
+            &["email", "id", "scores"],
+        );
+        assert_eq!(schema, expected);
+        assert!(schema.is_valid(&a));
+        assert!(schema.is_valid(&b));
+        assert!(!schema.is_valid(&parse(r#"{"id": 3, "email": 4, "scores": []}"#).unwrap()));
+    }
+}
@@ -0,0 +1,777 @@
+use alloc::collections::btree_map;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::tokenize::inexact_integer;
+use crate::{parse, Value};
+
+/// Options controlling how a [`Value`] is written out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SerializeOptions {
+    /// Number of spaces per nesting level, or `None` for compact output
+    pub indent: Option<usize>,
+    /// Pad object keys so every value of an object starts in the same column.
+    /// Only applies to objects in indented output, each object is aligned on its own.
+    pub align_object_values: bool,
+    /// Write non-integer floats with this many decimal places instead of the shortest text
+    /// that round-trips. Lossy, meant for output like telemetry where size matters more.
+    pub float_precision: Option<usize>,
+    /// Start the output with a byte order mark, U+FEFF, for consumers that expect one
+    pub write_bom: bool,
+    /// Escape `<`, `>`, `&` and the line and paragraph separators U+2028 and U+2029 in strings,
+    /// so the output can be embedded in an HTML `<script>` tag
+    pub html_safe: bool,
+    /// In indented output, keep arrays holding nothing but scalars on one line, ex. `[1, 2, 3]`.
+    /// Arrays with an array or object among their elements are still expanded.
+    pub inline_scalar_arrays: bool,
+    /// Write [`Value::RawNumber`] numbers as the exact text they were parsed from, keeping
+    /// details like the trailing zero of `1.50`. Otherwise they are written like any other number,
+    /// except those too large for `f64`, ex. `1e400`, which keep their text rather than turn `null`.
+    pub preserve_number_text: bool,
+    /// Leave out object members whose value is `null`, as if they weren't there. Nulls in
+    /// arrays are still written, see [`Value::filter_nulls`] for the same on the tree itself.
+    pub skip_nulls: bool,
+}
+
+impl SerializeOptions {
+    /// Indented output using two spaces per nesting level
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some(2),
+            ..Self::default()
+        }
+    }
+}
+
+/// Serializes `value` as compact JSON text
+pub fn to_string(value: &Value) -> String {
+    to_string_with_options(value, &SerializeOptions::default())
+}
+
+/// Serializes `value` as JSON text indented with two spaces
+pub fn to_string_pretty(value: &Value) -> String {
+    to_string_with_options(value, &SerializeOptions::pretty())
+}
+
+/// Serializes `value` as JSON text formatted according to `options`
+pub fn to_string_with_options(value: &Value, options: &SerializeOptions) -> String {
+    let mut output = String::new();
+    write_document(&mut output, value, options).expect("writing to a String cannot fail");
+    output
+}
+
+/// Like [`to_string_with_options`], failing with [`SerializeError::OutputTooLarge`] once the
+/// output would grow past `max_output_bytes`, the byte order mark included. Nothing more is
+/// serialized after that.
+pub fn try_to_string_with_options(
+    value: &Value,
+    options: &SerializeOptions,
+    max_output_bytes: usize,
+) -> Result<String, SerializeError> {
+    let mut output = Limited::new(String::new(), Some(max_output_bytes));
+    match write_document(&mut output, value, options) {
+        Ok(()) => Ok(output.writer),
+        // writing to a String can't fail otherwise
+        Err(_) => Err(SerializeError::OutputTooLarge),
+    }
+}
+
+/// Forwards to another writer until `remaining` bytes have been written, then fails
+struct Limited<W> {
+    writer: W,
+    remaining: Option<usize>,
+    exceeded: bool,
+}
+
+impl<W> Limited<W> {
+    fn new(writer: W, max_bytes: Option<usize>) -> Self {
+        Limited {
+            writer,
+            remaining: max_bytes,
+            exceeded: false,
+        }
+    }
+}
+
+impl<W: Write> Write for Limited<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(remaining) = &mut self.remaining {
+            if s.len() > *remaining {
+                self.exceeded = true;
+                return Err(fmt::Error);
+            }
+            *remaining -= s.len();
+        }
+        self.writer.write_str(s)
+    }
+}
+
+/// Serializes `value` as UTF-8 JSON text into `writer`
+#[cfg(feature = "std")]
+pub fn to_writer<W: std::io::Write>(writer: W, value: &Value, options: &SerializeOptions) -> std::io::Result<()> {
+    write_limited(writer, value, options, None)
+}
+
+/// Like [`to_writer`], stopping once the output would grow past `max_output_bytes` after
+/// writing what fit. That fails with an error of kind
+/// [`FileTooLarge`](std::io::ErrorKind::FileTooLarge) wrapping [`SerializeError::OutputTooLarge`],
+/// which [`get_ref`](std::io::Error::get_ref) and a downcast tell apart from the writer's own errors.
+#[cfg(feature = "std")]
+pub fn to_writer_with_limit<W: std::io::Write>(
+    writer: W,
+    value: &Value,
+    options: &SerializeOptions,
+    max_output_bytes: usize,
+) -> std::io::Result<()> {
+    write_limited(writer, value, options, Some(max_output_bytes))
+}
+
+#[cfg(feature = "std")]
+fn write_limited<W: std::io::Write>(
+    writer: W,
+    value: &Value,
+    options: &SerializeOptions,
+    max_output_bytes: Option<usize>,
+) -> std::io::Result<()> {
+    /// Forwards to an `io::Write`, holding on to the error `fmt::Write` has no room for
+    struct Adapter<W> {
+        writer: W,
+        error: Option<std::io::Error>,
+    }
+
+    impl<W: std::io::Write> Write for Adapter<W> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.writer.write_all(s.as_bytes()).map_err(|e| {
+                self.error = Some(e);
+                fmt::Error
+            })
+        }
+    }
+
+    let mut adapter = Limited::new(Adapter { writer, error: None }, max_output_bytes);
+    match write_document(&mut adapter, value, options) {
+        Ok(()) => Ok(()),
+        Err(_) if adapter.exceeded => Err(std::io::Error::new(
+            std::io::ErrorKind::FileTooLarge,
+            SerializeError::OutputTooLarge,
+        )),
+        Err(_) => Err(adapter.writer.error.unwrap_or_else(|| std::io::Error::other("formatting failed"))),
+    }
+}
+
+fn write_document<W: Write>(w: &mut W, value: &Value, options: &SerializeOptions) -> fmt::Result {
+    if options.write_bom {
+        w.write_char('\u{feff}')?;
+    }
+    write_value(w, value, options)
+}
+
+/// Why a value couldn't be serialized
+#[derive(Debug, PartialEq)]
+pub enum SerializeError {
+    /// The format needs an object at the top level
+    ExpectedObject,
+    /// An array or object under this key, where the format only allows scalars
+    NestedValue(String),
+    /// A [`Serializer`](crate::Serializer) was given a key outside an object, or right after
+    /// another key
+    UnexpectedKey,
+    /// A [`Serializer`](crate::Serializer) was given a value where an object key should be
+    ExpectedKey,
+    /// A [`Serializer`](crate::Serializer) was told to end a container that isn't open, or an
+    /// object whose last key has no value yet
+    UnexpectedEnd,
+    /// A [`Serializer`](crate::Serializer) was given more after the document was complete
+    DocumentComplete,
+    /// A [`Serializer`](crate::Serializer) was finished before the document was complete
+    Incomplete,
+    /// The output would grow past the byte limit it was given
+    OutputTooLarge,
+    /// Writing the output failed
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::ExpectedObject => f.write_str("expected an object at the top level"),
+            SerializeError::NestedValue(key) => write!(f, "expected a scalar under key {:?}", key),
+            SerializeError::UnexpectedKey => f.write_str("unexpected object key"),
+            SerializeError::ExpectedKey => f.write_str("expected an object key"),
+            SerializeError::UnexpectedEnd => f.write_str("no open container to end"),
+            SerializeError::DocumentComplete => f.write_str("the document is already complete"),
+            SerializeError::Incomplete => f.write_str("the document is incomplete"),
+            SerializeError::OutputTooLarge => f.write_str("serialized output exceeds the byte limit"),
+            #[cfg(feature = "std")]
+            SerializeError::Io(kind) => write!(f, "writing failed: {}", kind),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SerializeError {}
+
+/// Serializes a flat object of scalars as `application/x-www-form-urlencoded` text, ex.
+/// `{"a": 1, "b": "x y"}` as `a=1&b=x%20y`. Keys and values are percent-encoded, and `null`
+/// gives an empty value.
+pub fn to_query_string(value: &Value) -> Result<String, SerializeError> {
+    let Value::Object(map) = value else {
+        return Err(SerializeError::ExpectedObject);
+    };
+
+    let mut output = String::new();
+    let mut scalar = String::new();
+    for (key, value) in map {
+        scalar.clear();
+        match value {
+            Value::Null => {}
+            Value::Boolean(boolean) => scalar.push_str(if *boolean { "true" } else { "false" }),
+            Value::Number(number) => write_number(&mut scalar, *number, None).expect("writing to a String cannot fail"),
+            Value::Integer(integer) => write!(scalar, "{}", integer).expect("writing to a String cannot fail"),
+            Value::RawNumber(text) => scalar.push_str(text),
+            Value::String(string) => scalar.push_str(string),
+            Value::Array(_) | Value::Object(_) => return Err(SerializeError::NestedValue(String::from(&**key))),
+        }
+
+        if !output.is_empty() {
+            output.push('&');
+        }
+        push_percent_encoded(&mut output, key);
+        output.push('=');
+        push_percent_encoded(&mut output, &scalar);
+    }
+    Ok(output)
+}
+
+/// Appends `text` with every byte outside the URL unreserved characters percent-encoded
+fn push_percent_encoded(output: &mut String, text: &str) {
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => output.push(byte as char),
+            _ => {
+                let _ = write!(output, "%{:02X}", byte);
+            }
+        }
+    }
+}
+
+/// Writes `string` as a quoted JSON string, escaping characters straight into `w`
+pub fn write_escaped<W: Write>(w: &mut W, string: &str) -> fmt::Result {
+    write_escaped_with(w, string, false)
+}
+
+fn write_escaped_with<W: Write>(w: &mut W, string: &str, html_safe: bool) -> fmt::Result {
+    w.write_char('"')?;
+    for ch in string.chars() {
+        match ch {
+            '<' | '>' | '&' | '\u{2028}' | '\u{2029}' if html_safe => write!(w, "\\u{:04x}", ch as u32)?,
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            '\u{8}' => w.write_str("\\b")?,
+            '\u{c}' => w.write_str("\\f")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+/// A container being written, with the children still to come
+enum Frame<'a> {
+    Array {
+        elements: core::slice::Iter<'a, Value>,
+        first: bool,
+        /// Written on one line despite indentation, see [`SerializeOptions::inline_scalar_arrays`]
+        inline: bool,
+    },
+    Object {
+        entries: btree_map::Iter<'a, Arc<str>, Value>,
+        key_width: usize,
+        first: bool,
+    },
+}
+
+/// Writes `value` using an explicit stack of open containers rather than recursion, so
+/// arbitrarily deep values can't overflow the call stack
+fn write_value<W: Write>(w: &mut W, value: &Value, options: &SerializeOptions) -> fmt::Result {
+    let pretty = options.indent.is_some();
+    let written = |value: &Value| !(options.skip_nulls && *value == Value::Null);
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut next = Some(value);
+
+    loop {
+        // open a container or write a scalar
+        match next.take() {
+            Some(Value::Array(array)) if !array.is_empty() => {
+                let inline = pretty
+                    && options.inline_scalar_arrays
+                    && !array.iter().any(|element| matches!(element, Value::Array(_) | Value::Object(_)));
+                w.write_char('[')?;
+                stack.push(Frame::Array {
+                    elements: array.iter(),
+                    first: true,
+                    inline,
+                });
+            }
+            Some(Value::Object(map)) if map.values().any(written) => {
+                let key_width = if pretty && options.align_object_values {
+                    let keys = map.iter().filter(|(_, value)| written(value)).map(|(key, _)| key);
+                    keys.map(|key| escaped_width(key, options.html_safe)).max().unwrap_or(0)
+                } else {
+                    0
+                };
+                w.write_char('{')?;
+                stack.push(Frame::Object {
+                    entries: map.iter(),
+                    key_width,
+                    first: true,
+                });
+            }
+            Some(value) => write_scalar(w, value, options)?,
+            None => {}
+        }
+
+        // move on to the next child of the innermost open container, closing it when done
+        let depth = stack.len();
+        match stack.last_mut() {
+            None => return Ok(()),
+            Some(Frame::Array { elements, first, inline }) => match elements.next() {
+                Some(element) => {
+                    let first = core::mem::replace(first, false);
+                    if !first {
+                        w.write_char(',')?;
+                    }
+                    if !*inline {
+                        write_newline(w, options, depth)?;
+                    } else if !first {
+                        w.write_char(' ')?;
+                    }
+                    next = Some(element);
+                }
+                None => {
+                    if !*inline {
+                        write_newline(w, options, depth - 1)?;
+                    }
+                    stack.pop();
+                    w.write_char(']')?;
+                }
+            },
+            Some(Frame::Object {
+                entries,
+                key_width,
+                first,
+            }) => match entries.find(|(_, value)| written(value)) {
+                Some((key, value)) => {
+                    if !core::mem::replace(first, false) {
+                        w.write_char(',')?;
+                    }
+                    write_newline(w, options, depth)?;
+                    write_escaped_with(w, key, options.html_safe)?;
+                    w.write_char(':')?;
+                    if pretty {
+                        w.write_char(' ')?;
+                    }
+                    write_padding(w, key_width.saturating_sub(escaped_width(key, options.html_safe)))?;
+                    next = Some(value);
+                }
+                None => {
+                    stack.pop();
+                    write_newline(w, options, depth - 1)?;
+                    w.write_char('}')?;
+                }
+            },
+        }
+    }
+}
+
+/// Writes anything but a non-empty container
+fn write_scalar<W: Write>(w: &mut W, value: &Value, options: &SerializeOptions) -> fmt::Result {
+    match value {
+        Value::Null => w.write_str("null"),
+        Value::Boolean(true) => w.write_str("true"),
+        Value::Boolean(false) => w.write_str("false"),
+        Value::Number(number) => write_number(w, *number, options.float_precision),
+        Value::Integer(integer) => write!(w, "{}", integer),
+        Value::RawNumber(text) if options.preserve_number_text => w.write_str(text),
+        Value::RawNumber(text) => match parse(text) {
+            // too large for `f64`, ex. `1e400`, where `null` would lose the number
+            Ok(Value::Number(number)) if !number.is_finite() => w.write_str(text),
+            Ok(number @ (Value::Number(_) | Value::Integer(_))) => write_scalar(w, &number, options),
+            // text that isn't a number, only possible if the value was built by hand
+            _ => w.write_str("null"),
+        },
+        Value::String(string) => write_escaped_with(w, string, options.html_safe),
+        Value::Array(_) => w.write_str("[]"),
+        Value::Object(_) => w.write_str("{}"),
+    }
+}
+
+fn write_number<W: Write>(w: &mut W, number: f64, precision: Option<usize>) -> fmt::Result {
+    // JSON has no representation for NaN or the infinities
+    if !number.is_finite() {
+        return w.write_str("null");
+    }
+
+    if let Some(precision) = precision.filter(|_| number % 1.0 != 0.0) {
+        return write!(w, "{:.*}", precision, number);
+    }
+
+    // both notations print the shortest digits that round-trip, so whichever
+    // is shorter is the minimal text for this number. Negative zero keeps its sign as `-0`.
+    let mut digits = [0u8; 32];
+    let positional = format_into(&mut digits, format_args!("{}", number));
+    let mut exponent_digits = [0u8; 32];
+    let exponent = format_into(&mut exponent_digits, format_args!("{:e}", number));
+
+    // whole numbers `f64` can't hold exactly would read back as `Value::Integer` without the exponent
+    let reads_as_integer = |text: &str| !text.contains('.') && inexact_integer(text).is_some();
+
+    match (positional, exponent) {
+        (Some(positional), Some(exponent)) if exponent.len() < positional.len() || reads_as_integer(positional) => {
+            w.write_str(exponent)
+        }
+        (Some(positional), _) => w.write_str(positional),
+        (None, Some(exponent)) => w.write_str(exponent),
+        (None, None) => write!(w, "{:e}", number),
+    }
+}
+
+/// Formats into a fixed stack buffer, returning `None` when the text doesn't fit
+fn format_into<'a>(buffer: &'a mut [u8], args: fmt::Arguments) -> Option<&'a str> {
+    struct Cursor<'b> {
+        buffer: &'b mut [u8],
+        len: usize,
+    }
+
+    impl Write for Cursor<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            let target = self.buffer.get_mut(self.len..end).ok_or(fmt::Error)?;
+            target.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut cursor = Cursor { buffer, len: 0 };
+    cursor.write_fmt(args).ok()?;
+    let len = cursor.len;
+    core::str::from_utf8(&buffer[..len]).ok()
+}
+
+/// Number of characters `string` takes up once escaped, excluding the quotes
+fn escaped_width(string: &str, html_safe: bool) -> usize {
+    string
+        .chars()
+        .map(|ch| match ch {
+            '<' | '>' | '&' | '\u{2028}' | '\u{2029}' if html_safe => 6,
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+            c if (c as u32) < 0x20 => 6,
+            _ => 1,
+        })
+        .sum()
+}
+
+fn write_padding<W: Write>(w: &mut W, width: usize) -> fmt::Result {
+    for _ in 0..width {
+        w.write_char(' ')?;
+    }
+    Ok(())
+}
+
+fn write_newline<W: Write>(w: &mut W, options: &SerializeOptions, depth: usize) -> fmt::Result {
+    match options.indent {
+        Some(indent) => {
+            w.write_char('\n')?;
+            write_padding(w, indent * depth)
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_with_options, ParseOptions};
+
+    #[test]
+    fn test_to_string_compact() {
+        let value = parse(r#"{"b": [1, 2.5, null], "a": "x\"y", "c": {"d": true}}"#).unwrap();
+        assert_eq!(to_string(&value), r#"{"a":"x\"y","b":[1,2.5,null],"c":{"d":true}}"#);
+    }
+
+    #[test]
+    fn test_to_string_pretty() {
+        let value = parse(r#"{"a": [1, {}], "b": []}"#).unwrap();
+        let expected = "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": []\n}";
+        assert_eq!(to_string_pretty(&value), expected);
+    }
+
+    #[test]
+    fn test_align_object_values() {
+        let value = parse(r#"{"a": 1, "long_key": {"xy": 2, "z": 3}, "mid": [4]}"#).unwrap();
+        let options = SerializeOptions {
+            align_object_values: true,
+            ..SerializeOptions::pretty()
+        };
+        let expected = concat!(
+            "{\n",
+            "  \"a\":        1,\n",
+            "  \"long_key\": {\n",
+            "    \"xy\": 2,\n",
+            "    \"z\":  3\n",
+            "  },\n",
+            "  \"mid\":      [\n",
+            "    4\n",
+            "  ]\n",
+            "}",
+        );
+        assert_eq!(to_string_with_options(&value, &options), expected);
+    }
+
+    #[test]
+    fn test_inline_scalar_arrays() {
+        let options = SerializeOptions {
+            inline_scalar_arrays: true,
+            ..SerializeOptions::pretty()
+        };
+        let value = parse(r#"{"a": [1, "two", null], "b": [[3, 4], {"c": [true]}], "d": []}"#).unwrap();
+        assert_eq!(
+            to_string_with_options(&value, &options),
+            "{\n  \"a\": [1, \"two\", null],\n  \"b\": [\n    [3, 4],\n    {\n      \"c\": [true]\n    }\n  ],\n  \
+             \"d\": []\n}"
+        );
+
+        let compact = SerializeOptions {
+            inline_scalar_arrays: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(to_string_with_options(&value, &compact), to_string(&value));
+    }
+
+    #[test]
+    fn test_preserve_number_text() {
+        let options = ParseOptions {
+            numbers_as_strings: true,
+            ..ParseOptions::default()
+        };
+        let value = parse_with_options("[1.50, 100.0, 1e400, 12345678901234567890123]", &options).unwrap();
+        let preserve = SerializeOptions {
+            preserve_number_text: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(to_string_with_options(&value, &preserve), "[1.50,100.0,1e400,12345678901234567890123]");
+        assert_eq!(to_string(&value), "[1.5,100,1e400,12345678901234567890123]");
+        assert_eq!(to_string(&Value::RawNumber("-1e400".into())), "-1e400");
+    }
+
+    #[test]
+    fn test_max_output_bytes() {
+        let value = Value::Array((0..10_000).map(|i| Value::String(format!("item {}", i))).collect());
+        let options = SerializeOptions::default();
+        assert_eq!(try_to_string_with_options(&value, &options, 1024), Err(SerializeError::OutputTooLarge));
+
+        let small = parse(r#"{"a": [1, 2]}"#).unwrap();
+        assert_eq!(try_to_string_with_options(&small, &options, 11), Ok(String::from(r#"{"a":[1,2]}"#)));
+        let bom = SerializeOptions {
+            write_bom: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(try_to_string_with_options(&small, &bom, 11), Err(SerializeError::OutputTooLarge));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_max_output_bytes_to_writer() {
+        let value = Value::Array((0..10_000).map(|i| Value::String(format!("item {}", i))).collect());
+        let mut bytes = Vec::new();
+        let err = to_writer_with_limit(&mut bytes, &value, &SerializeOptions::default(), 1024).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::FileTooLarge);
+        let cause = err.get_ref().and_then(|cause| cause.downcast_ref::<SerializeError>());
+        assert_eq!(cause, Some(&SerializeError::OutputTooLarge));
+        assert!(bytes.len() <= 1024);
+    }
+
+    #[test]
+    fn test_write_escaped() {
+        let mut buffer = String::from("key=");
+        write_escaped(&mut buffer, "tab\there \"quoted\" \\ \u{1}").unwrap();
+        write_escaped(&mut buffer, "").unwrap();
+        assert_eq!(buffer, r#"key="tab\there \"quoted\" \\ \u0001""""#);
+    }
+
+    fn number_to_string(number: f64) -> String {
+        to_string(&Value::Number(number))
+    }
+
+    #[test]
+    fn test_shortest_float() {
+        assert_eq!(number_to_string(0.1), "0.1");
+        assert_eq!(number_to_string(1e-7), "1e-7");
+        assert_eq!(number_to_string(123456789.0), "123456789");
+        assert_eq!(number_to_string(1e21), "1e21");
+        assert_eq!(number_to_string(-2.5e-300), "-2.5e-300");
+        assert_eq!(number_to_string(f64::MIN_POSITIVE / 4.0), "5.562684646268003e-309");
+        assert_eq!(number_to_string(5e-324), "5e-324");
+    }
+
+    #[test]
+    fn test_float_round_trip() {
+        let mut values = vec![0.1, 1e-7, 123456789.0, f64::MAX, f64::MIN_POSITIVE, 5e-324, -1.5e-310];
+
+        // sweep a spread of bit patterns, which includes subnormals and large exponents
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..10_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            values.push(f64::from_bits(state));
+        }
+
+        for number in values.into_iter().filter(|number| number.is_finite()) {
+            let text = number_to_string(number);
+            match parse(&text).unwrap() {
+                Value::Number(parsed) => assert_eq!(parsed.to_bits(), number.to_bits(), "{}", text),
+                other => panic!("{} parsed as {:?}", text, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_negative_zero() {
+        assert_eq!(number_to_string(-0.0), "-0");
+        assert_eq!(number_to_string(0.0), "0");
+
+        match parse(&number_to_string(-0.0)).unwrap() {
+            Value::Number(number) => assert!(number == 0.0 && number.is_sign_negative()),
+            other => panic!("parsed as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_float_precision() {
+        let options = SerializeOptions {
+            float_precision: Some(2),
+            ..SerializeOptions::default()
+        };
+        let pi = parse("3.14159").unwrap();
+        assert_eq!(to_string_with_options(&pi, &options), "3.14");
+        assert_eq!(to_string(&pi), "3.14159");
+
+        let value = parse("[42, -0.0005, 1e30, 12345678901234567890123]").unwrap();
+        assert_eq!(
+            to_string_with_options(&value, &options),
+            "[42,-0.00,1e30,12345678901234567890123]"
+        );
+    }
+
+    #[test]
+    fn test_write_bom() {
+        let value = parse(r#"{"a": "\ufeff"}"#).unwrap();
+        let options = SerializeOptions {
+            write_bom: true,
+            ..SerializeOptions::default()
+        };
+
+        let text = to_string_with_options(&value, &options);
+        assert_eq!(text, "\u{feff}{\"a\":\"\u{feff}\"}");
+        assert_eq!(to_string(&value), "{\"a\":\"\u{feff}\"}");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_bom_to_writer() {
+        let value = parse(r#"{"a": "\ufeff"}"#).unwrap();
+        let options = SerializeOptions {
+            write_bom: true,
+            ..SerializeOptions::default()
+        };
+        let mut bytes = Vec::new();
+        to_writer(&mut bytes, &value, &options).unwrap();
+        assert!(bytes.starts_with(b"\xef\xbb\xbf{"));
+        assert_eq!(bytes.windows(3).filter(|window| *window == b"\xef\xbb\xbf").count(), 2);
+
+        let mut bytes = Vec::new();
+        to_writer(&mut bytes, &value, &SerializeOptions::default()).unwrap();
+        assert_eq!(bytes, to_string(&value).into_bytes());
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        let depth = 50_000;
+        let mut value = Value::Array(vec![]);
+        for _ in 1..depth {
+            value = Value::Array(vec![value]);
+        }
+
+        let text = to_string(&value);
+        assert_eq!(text, format!("{}{}", "[".repeat(depth), "]".repeat(depth)));
+
+        // parsing, comparing and dropping this deep a value do recurse, so they get a larger stack
+        std::thread::Builder::new()
+            .stack_size(512 << 20)
+            .spawn(move || assert_eq!(parse(&text).unwrap(), value))
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_to_query_string() {
+        let value = parse(r#"{"b": "x y", "a": 1, "c&d": "é=1/2", "e": null, "f": true, "g": -2.5}"#).unwrap();
+        assert_eq!(
+            to_query_string(&value).unwrap(),
+            "a=1&b=x%20y&c%26d=%C3%A9%3D1%2F2&e=&f=true&g=-2.5"
+        );
+        assert_eq!(to_query_string(&parse("{}").unwrap()).unwrap(), "");
+    }
+
+    #[test]
+    fn test_to_query_string_errors() {
+        let nested = parse(r#"{"a": 1, "b": {"c": 2}}"#).unwrap();
+        assert_eq!(to_query_string(&nested), Err(SerializeError::NestedValue("b".into())));
+        assert_eq!(to_query_string(&parse("[1]").unwrap()), Err(SerializeError::ExpectedObject));
+    }
+
+    #[test]
+    fn test_html_safe() {
+        let value = parse(r#"{"<key>": "</script><b>&amp;\u2028\u2029", "n": [1, "plain \"text\""]}"#).unwrap();
+        let options = SerializeOptions {
+            html_safe: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            to_string_with_options(&value, &options),
+            r#"{"\u003ckey\u003e":"\u003c/script\u003e\u003cb\u003e\u0026amp;\u2028\u2029","n":[1,"plain \"text\""]}"#
+        );
+        assert_eq!(parse(&to_string_with_options(&value, &options)).unwrap(), value);
+        assert_eq!(
+            to_string(&value),
+            "{\"<key>\":\"</script><b>&amp;\u{2028}\u{2029}\",\"n\":[1,\"plain \\\"text\\\"\"]}"
+        );
+    }
+
+    #[test]
+    fn test_skip_nulls() {
+        let value = parse(r#"{"a": null, "b": [null, 1], "c": {"d": null}, "e": {"f": null, "g": 2}}"#).unwrap();
+        let options = SerializeOptions {
+            skip_nulls: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(to_string_with_options(&value, &options), r#"{"b":[null,1],"c":{},"e":{"g":2}}"#);
+        assert_eq!(to_string(&value), r#"{"a":null,"b":[null,1],"c":{"d":null},"e":{"f":null,"g":2}}"#);
+
+        let options = SerializeOptions {
+            skip_nulls: true,
+            ..SerializeOptions::pretty()
+        };
+        assert_eq!(to_string_with_options(value.get("c").unwrap(), &options), "{}");
+        assert_eq!(to_string_with_options(&Value::Null, &options), "null");
+    }
+}
@@ -0,0 +1,162 @@
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::parse::{parse_value_at, ParseError, ParseOptions, TokenParseError};
+use crate::tokenize::{next_token, Token, TokenizeError};
+use crate::Value;
+
+/// Parses a top-level array one element at a time.
+///
+/// Tokens are only read as far as the element being parsed, and each element is handed out
+/// as soon as it is complete, so the whole array never has to be held in memory. Iteration
+/// stops after the first error. Errors parsing an element are wrapped in
+/// [`TokenParseError::InValue`] with the element's index as the path, ex. `/2`.
+pub fn parse_array_stream(input: &str) -> impl Iterator<Item = Result<Value, ParseError>> {
+    ArrayStream {
+        chars: input.chars().collect(),
+        index: 0,
+        state: State::Start,
+        elements: 0,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum State {
+    /// Nothing has been read yet
+    Start,
+    /// The opening bracket was read, the next token is an element or the closing bracket
+    FirstElement,
+    /// An element was read, the next token is a comma or the closing bracket
+    Separator,
+    Finished,
+}
+
+struct ArrayStream {
+    chars: Vec<char>,
+    index: usize,
+    state: State,
+    /// Number of elements read so far
+    elements: usize,
+}
+
+impl ArrayStream {
+    fn next_token(&mut self) -> Result<Token, ParseError> {
+        match next_token(&self.chars, &mut self.index, &ParseOptions::default(), false)? {
+            Some(token) => Ok(token),
+            None => Err(TokenizeError::UnexpectedEof.into()),
+        }
+    }
+
+    fn next_element(&mut self) -> Result<Option<Value>, ParseError> {
+        if self.state == State::Start {
+            if self.next_token()? != Token::LeftBracket {
+                return Err(TokenParseError::ExpectedArray.into());
+            }
+            self.state = State::FirstElement;
+        }
+
+        let first = self.next_token()?;
+        let first = match (&self.state, first) {
+            (_, Token::RightBracket) => {
+                self.state = State::Finished;
+                return Ok(None);
+            }
+            (State::FirstElement, token) => token,
+            (_, Token::Comma) => self.next_token()?,
+            _ => return Err(TokenParseError::ExpectedComma.into()),
+        };
+
+        // gather the tokens of this element only, tracking nesting to find where it ends
+        let mut depth: usize = 0;
+        let mut tokens = vec![first];
+        loop {
+            match tokens.last() {
+                Some(Token::LeftBrace | Token::LeftBracket) => depth += 1,
+                Some(Token::RightBrace | Token::RightBracket) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+            let token = self.next_token()?;
+            tokens.push(token);
+        }
+
+        let value = parse_value_at(&tokens, &mut 0).map_err(|err| err.in_value(&self.elements.to_string()))?;
+        self.state = State::Separator;
+        self.elements += 1;
+        Ok(Some(value))
+    }
+}
+
+impl Iterator for ArrayStream {
+    type Item = Result<Value, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Finished {
+            return None;
+        }
+
+        let result = self.next_element();
+        if result.is_err() {
+            self.state = State::Finished;
+        }
+        result.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_array_stream() {
+        let values: Vec<_> = parse_array_stream(" [1, [2, {\"a\": 2}], 3] ").collect();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0], Ok(Value::Number(1.0)));
+        assert_eq!(values[2], Ok(Value::Number(3.0)));
+
+        assert_eq!(parse_array_stream("[]").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_array_stream_malformed_element() {
+        let in_value = |path: &str, cause| TokenParseError::InValue {
+            path: path.into(),
+            cause: Box::new(cause),
+        };
+        let mut values = parse_array_stream(r#"[1, 2, {"a" 3}, 4]"#);
+        assert_eq!(values.next(), Some(Ok(Value::Number(1.0))));
+        assert_eq!(values.next(), Some(Ok(Value::Number(2.0))));
+        assert_eq!(values.next(), Some(Err(in_value("/2", TokenParseError::ExpectedColon).into())));
+        assert_eq!(values.next(), None);
+
+        let mut values = parse_array_stream(r#"[{"a": [1]}, {"b": [1 2]}]"#);
+        assert!(values.next().unwrap().is_ok());
+        assert_eq!(values.next(), Some(Err(in_value("/1/b", TokenParseError::ExpectedComma).into())));
+
+        // later elements aren't tokenized until they are reached
+        let mut values = parse_array_stream("[true, nul]");
+        assert_eq!(values.next(), Some(Ok(Value::Boolean(true))));
+        assert_eq!(values.next(), Some(Err(TokenizeError::UnfinishedLiteralValue.into())));
+        assert_eq!(values.next(), None);
+    }
+
+    #[test]
+    fn test_parse_array_stream_not_array() {
+        let mut values = parse_array_stream("{}");
+        assert_eq!(values.next(), Some(Err(TokenParseError::ExpectedArray.into())));
+        assert_eq!(values.next(), None);
+
+        let mut values = parse_array_stream("[1 2]");
+        assert_eq!(values.next(), Some(Ok(Value::Number(1.0))));
+        assert_eq!(values.next(), Some(Err(TokenParseError::ExpectedComma.into())));
+
+        let mut values = parse_array_stream("[1,");
+        assert_eq!(values.next(), Some(Ok(Value::Number(1.0))));
+        assert_eq!(values.next(), Some(Err(TokenizeError::UnexpectedEof.into())));
+    }
+}
@@ -1,4 +1,9 @@
-use std::num::ParseFloatError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::num::ParseFloatError;
+
+use crate::parse::{ParseError, ParseOptions};
+use crate::Value;
 
 #[derive(Debug, PartialEq)]
 pub enum Token {
@@ -22,8 +27,23 @@ pub enum Token {
     True,
     /// Any number literal
     Number(f64),
+    /// An integer literal `f64` can't represent exactly, kept exact rather than rounded
+    Integer(i128),
     /// Key of the key/value pair or a string value
     String(String),
+    /// An unquoted object key, only produced when unquoted keys are allowed
+    Identifier(String),
+    /// A number with a unit suffix like `10s`, as interpreted by
+    /// [`ParseOptions::number_suffix_handler`], or any number as [`Value::RawNumber`] when
+    /// [`ParseOptions::numbers_as_strings`] is set
+    Custom(Value),
+}
+
+/// Byte range a token covers in the source text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug, PartialEq)]
@@ -31,35 +51,142 @@ pub enum TokenizeError {
     UnfinishedLiteralValue,
     UnclosedQuotes,
     UnexpectedEof,
+    /// A `/* block */` comment was never closed
+    UnclosedComment,
     CharNotRecognized(char),
     ParseNumberError(ParseFloatError),
+    /// A number the JSON grammar doesn't allow, ex. `01` or `1.`, while
+    /// [`ParseOptions::strict_numbers`] is set
+    InvalidNumber(String),
+    /// An unescaped control character inside a string while
+    /// [`ParseOptions::reject_control_characters`] is set
+    ControlCharacter(char),
 }
 
-pub fn tokenize(input: String) -> Result<Vec<Token>, TokenizeError> {
+pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizeError> {
     let chars: Vec<char> = input.chars().collect();
     let mut index = 0;
 
     let mut tokens = Vec::new();
 
-    while index < chars.len() {
-        let token = make_token(&chars, &mut index)?;
+    while let Some(token) = next_token(&chars, &mut index, &ParseOptions::default(), false)? {
         tokens.push(token);
-        index += 1;
     }
 
     Ok(tokens)
 }
 
-fn make_token(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
-    let mut ch = chars[*index];
-    while ch.is_ascii_whitespace() {
-        *index += 1;
-        if *index >= chars.len() {
-            return Err(TokenizeError::UnexpectedEof);
+/// Like [`tokenize`], pairing every token with the byte range it covers in `input`.
+/// Whitespace between tokens isn't part of any span.
+pub fn tokenize_spanned(input: &str) -> Result<Vec<(Token, Span)>, TokenizeError> {
+    let (tokens, spans) = tokenize_with_spans(input, &ParseOptions::default()).map_err(|err| match err {
+        ParseError::TokenizeError(err) => err,
+        // the default options set no token budget, so tokenizing can't fail any other way
+        err => unreachable!("{:?}", err),
+    })?;
+    Ok(tokens.into_iter().zip(spans).collect())
+}
+
+/// Like [`tokenize`], also returning the byte range of every token. Stops as soon as the
+/// token budget in `options` is exceeded.
+pub(crate) fn tokenize_with_spans(input: &str, options: &ParseOptions) -> Result<(Vec<Token>, Vec<Span>), ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    // byte offset of every char, plus the end of the input
+    let offsets: Vec<usize> = input
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(core::iter::once(input.len()))
+        .collect();
+    let mut index = 0;
+
+    let mut tokens = Vec::with_capacity(options.token_capacity);
+    let mut spans = Vec::with_capacity(options.token_capacity);
+    // whether each open container is an array, for telling a decimal comma from an element separator
+    let mut in_array = Vec::new();
+
+    loop {
+        skip_whitespace(&chars, &mut index, options)?;
+        let start = index;
+        let token = match next_token(&chars, &mut index, options, in_array.last() == Some(&true))? {
+            Some(token) => token,
+            None => break,
+        };
+        match token {
+            Token::LeftBracket => in_array.push(true),
+            Token::LeftBrace => in_array.push(false),
+            Token::RightBracket | Token::RightBrace => {
+                in_array.pop();
+            }
+            _ => {}
+        }
+        tokens.push(token);
+        if options.max_tokens.is_some_and(|max_tokens| tokens.len() > max_tokens) {
+            return Err(ParseError::BudgetExceeded);
         }
-        ch = chars[*index];
+        spans.push(Span {
+            start: offsets[start],
+            end: offsets[index],
+        });
     }
 
+    Ok((tokens, spans))
+}
+
+/// Moves `index` past whitespace, and comments when they are allowed
+fn skip_whitespace(chars: &[char], index: &mut usize, options: &ParseOptions) -> Result<(), TokenizeError> {
+    while *index < chars.len() {
+        match (chars[*index], chars.get(*index + 1)) {
+            (c, _) if c.is_ascii_whitespace() && !(options.strict_whitespace && c == '\u{c}') => *index += 1,
+            ('/', Some('/')) if options.allow_comments => {
+                while *index < chars.len() && chars[*index] != '\n' {
+                    *index += 1;
+                }
+            }
+            ('/', Some('*')) if options.allow_comments => {
+                *index += 2;
+                loop {
+                    match (chars.get(*index), chars.get(*index + 1)) {
+                        (Some('*'), Some('/')) => break,
+                        (Some(_), _) => *index += 1,
+                        (None, _) => return Err(TokenizeError::UnclosedComment),
+                    }
+                }
+                *index += 2;
+            }
+            _ => break,
+        }
+    }
+    Ok(())
+}
+
+/// Tokenizes the next token at or after `index`, skipping whitespace and leaving `index`
+/// just past the token. Returns `None` once only whitespace is left. `in_array` is whether
+/// the innermost open container is an array.
+pub(crate) fn next_token(
+    chars: &[char],
+    index: &mut usize,
+    options: &ParseOptions,
+    in_array: bool,
+) -> Result<Option<Token>, TokenizeError> {
+    skip_whitespace(chars, index, options)?;
+    if *index >= chars.len() {
+        return Ok(None);
+    }
+
+    let token = make_token(chars, index, options, in_array)?;
+    *index += 1;
+    Ok(Some(token))
+}
+
+/// Tokenizes the token starting at `index`, leaving `index` on its last character
+fn make_token(
+    chars: &[char],
+    index: &mut usize,
+    options: &ParseOptions,
+    in_array: bool,
+) -> Result<Token, TokenizeError> {
+    let ch = chars[*index];
+
     let token = match ch {
         '{' => Token::LeftBrace,
         '}' => Token::RightBrace,
@@ -67,35 +194,107 @@ fn make_token(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeErr
         ']' => Token::RightBracket,
         ':' => Token::Colon,
         ',' => Token::Comma,
+        c if options.allow_unquoted_keys && (c.is_alphabetic() || c == '_' || c == '$') => {
+            tokenize_identifier(chars, index, options)
+        }
         'n' => tokenize_literal(String::from("null"), chars, index)?,
         'f' => tokenize_literal(String::from("false"), chars, index)?,
         't' => tokenize_literal(String::from("true"), chars, index)?,
-        '"' => tokenize_string(chars, index)?,
-        c if c.is_ascii_digit() || c == '-' => tokenize_float(chars, index)?,
+        '"' => tokenize_string(chars, index, options)?,
+        '\'' if options.allow_single_quotes => tokenize_string(chars, index, options)?,
+        'N' if options.allow_nan => tokenize_literal(String::from("NaN"), chars, index)?,
+        'I' if options.allow_infinity => tokenize_literal(String::from("Infinity"), chars, index)?,
+        '-' if options.allow_infinity && chars.get(*index + 1) == Some(&'I') => {
+            tokenize_literal(String::from("-Infinity"), chars, index)?
+        }
+        c if c.is_ascii_digit() || c == '-' => tokenize_float(chars, index, options, in_array)?,
         _ => return Err(TokenizeError::CharNotRecognized(ch)),
     };
 
     Ok(token)
 }
 
-fn tokenize_float(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
+fn tokenize_float(
+    chars: &[char],
+    index: &mut usize,
+    options: &ParseOptions,
+    in_array: bool,
+) -> Result<Token, TokenizeError> {
+    let start = *index;
     let mut unparsed = String::new();
     let mut has_decimal = false;
+    let mut has_exponent = false;
+
+    if chars[*index] == '-' {
+        unparsed.push('-');
+        *index += 1;
+    }
 
     while *index < chars.len() {
         let ch = chars[*index];
 
         match ch {
             c if c.is_ascii_digit() => unparsed.push(c),
-            c if c == '.' && !has_decimal => {
+            c if c == '.' && !has_decimal && !has_exponent => {
                 unparsed.push(c);
                 has_decimal = true;
             }
+            // a localized decimal point, only between two digits, and never a `,` directly
+            // inside an array where it separates the elements
+            c if c == options.decimal_separator
+                && (!in_array || c != ',')
+                && !has_decimal
+                && !has_exponent
+                && unparsed.ends_with(|c: char| c.is_ascii_digit())
+                && chars.get(*index + 1).is_some_and(char::is_ascii_digit) =>
+            {
+                unparsed.push('.');
+                has_decimal = true;
+            }
+            c if (c == 'e' || c == 'E') && !has_exponent => {
+                unparsed.push(c);
+                has_exponent = true;
+                // the exponent may carry its own sign
+                if let Some(&sign) = chars.get(*index + 1).filter(|&&c| c == '+' || c == '-') {
+                    unparsed.push(sign);
+                    *index += 1;
+                }
+            }
             _ => break,
         }
 
         *index += 1;
     }
+    // step back onto the last character of the number
+    *index -= 1;
+
+    // a unit suffix straight after the number, ex. `10s`, goes to the handler along with it
+    if let Some(handler) = options.number_suffix_handler {
+        let suffix = chars[*index + 1..].iter().take_while(|c| c.is_alphabetic() || **c == '%').count();
+        if suffix > 0 {
+            *index += suffix;
+            let raw: String = chars[start..=*index].iter().collect();
+            return handler.0(&raw).map(Token::Custom);
+        }
+    }
+
+    if options.strict_numbers && !is_json_number(&unparsed) {
+        return Err(TokenizeError::InvalidNumber(unparsed));
+    }
+
+    if options.numbers_as_strings {
+        // only kept once it's known to be a number, so the text is still one when written back out
+        if let Err(e) = unparsed.parse::<f64>() {
+            return Err(TokenizeError::ParseNumberError(e));
+        }
+        return Ok(Token::Custom(Value::RawNumber(unparsed)));
+    }
+
+    if !has_decimal && !has_exponent {
+        if let Some(num) = inexact_integer(&unparsed) {
+            return Ok(Token::Integer(num));
+        }
+    }
 
     match unparsed.parse() {
         Ok(num) => Ok(Token::Number(num)),
@@ -103,36 +302,131 @@ fn tokenize_float(chars: &Vec<char>, index: &mut usize) -> Result<Token, Tokeniz
     }
 }
 
-fn tokenize_literal(str: String, chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
-    for expected_char in str.chars() {
-        let ch = chars[*index];
-        if ch != expected_char {
+/// Parses a single number written exactly as the JSON grammar spells it, with the same rules
+/// the tokenizer applies with [`ParseOptions::strict_numbers`]. Anything after the number,
+/// including whitespace, is an error. Numbers too large for `f64` become infinite.
+pub fn parse_number(s: &str) -> Result<f64, TokenizeError> {
+    let chars: Vec<char> = s.chars().collect();
+    match chars.first() {
+        None => return Err(TokenizeError::UnexpectedEof),
+        Some(&c) if !c.is_ascii_digit() && c != '-' => return Err(TokenizeError::CharNotRecognized(c)),
+        Some(_) => {}
+    }
+
+    let options = ParseOptions {
+        strict_numbers: true,
+        ..ParseOptions::default()
+    };
+    let mut index = 0;
+    let token = tokenize_float(&chars, &mut index, &options, false)?;
+    if let Some(&c) = chars.get(index + 1) {
+        return Err(TokenizeError::CharNotRecognized(c));
+    }
+    match token {
+        Token::Number(number) => Ok(number),
+        Token::Integer(integer) => Ok(integer as f64),
+        token => unreachable!("the default options read numbers as numbers, got {:?}", token),
+    }
+}
+
+/// Reads `text` as an integer if it's one that has to be kept as `i128` rather than `f64`:
+/// one beyond `i64`, or one `f64` would round, ex. `1700000000123456789`
+pub(crate) fn inexact_integer(text: &str) -> Option<i128> {
+    let integer = text.parse::<i128>().ok()?;
+    // converting back through `i128` rather than `i64`, which would saturate at `i64::MAX`
+    let rounds = i64::try_from(integer).is_err() || integer as f64 as i128 != integer;
+    rounds.then_some(integer)
+}
+
+/// Whether `text` is a number exactly as the JSON grammar spells it: no leading zeros, and
+/// digits on both sides of a decimal point
+pub(crate) fn is_json_number(text: &str) -> bool {
+    fn digits(bytes: &[u8]) -> usize {
+        bytes.iter().take_while(|byte| byte.is_ascii_digit()).count()
+    }
+
+    let bytes = text.as_bytes();
+    let mut i = usize::from(bytes.first() == Some(&b'-'));
+    match digits(&bytes[i..]) {
+        0 => return false,
+        n if n > 1 && bytes[i] == b'0' => return false,
+        n => i += n,
+    }
+    if bytes.get(i) == Some(&b'.') {
+        match digits(&bytes[i + 1..]) {
+            0 => return false,
+            n => i += 1 + n,
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        match digits(&bytes[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == bytes.len()
+}
+
+fn tokenize_literal(str: String, chars: &[char], index: &mut usize) -> Result<Token, TokenizeError> {
+    for (offset, expected_char) in str.chars().enumerate() {
+        if offset > 0 {
+            *index += 1;
+        }
+        if chars.get(*index) != Some(&expected_char) {
             return Err(TokenizeError::UnfinishedLiteralValue);
         }
-        *index += 1;
     }
 
     match str.as_str() {
         "null" => Ok(Token::Null),
         "false" => Ok(Token::False),
         "true" => Ok(Token::True),
+        "NaN" => Ok(Token::Number(f64::NAN)),
+        "Infinity" => Ok(Token::Number(f64::INFINITY)),
+        "-Infinity" => Ok(Token::Number(f64::NEG_INFINITY)),
         _ => Err(TokenizeError::UnfinishedLiteralValue),
     }
 }
 
-fn tokenize_string(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
-    let mut string = String::new();
+/// Tokenizes a bare word, which is a literal if it spells one and an unquoted key otherwise
+fn tokenize_identifier(chars: &[char], index: &mut usize, options: &ParseOptions) -> Token {
+    let mut word = String::new();
+    while let Some(&c) = chars.get(*index).filter(|c| c.is_alphanumeric() || **c == '_' || **c == '$') {
+        word.push(c);
+        *index += 1;
+    }
+    // step back onto the last character of the word
+    *index -= 1;
+
+    match word.as_str() {
+        "null" => Token::Null,
+        "false" => Token::False,
+        "true" => Token::True,
+        "NaN" if options.allow_nan => Token::Number(f64::NAN),
+        "Infinity" if options.allow_infinity => Token::Number(f64::INFINITY),
+        _ => Token::Identifier(word),
+    }
+}
+
+fn tokenize_string(chars: &[char], index: &mut usize, options: &ParseOptions) -> Result<Token, TokenizeError> {
+    let quote = chars[*index];
+    let mut string = String::with_capacity(options.string_capacity);
     let mut is_escaping = false;
 
     loop {
         *index += 1;
-        if *index > chars.len() {
+        if *index >= chars.len() {
             return Err(TokenizeError::UnclosedQuotes);
         }
 
         let ch = chars[*index];
         match ch {
-            '"' if !is_escaping => break,
+            c if c == quote && !is_escaping => break,
+            c if options.reject_control_characters && c < ' ' => return Err(TokenizeError::ControlCharacter(c)),
             '\\' => is_escaping = !is_escaping,
             _ => is_escaping = false,
         }
@@ -150,13 +444,13 @@ mod tests {
     fn test_comma() {
         let input = String::from(",");
         let expected = vec![Token::Comma];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     fn test_literal(literal: &str, expected: Token) {
         let input = String::from(literal);
         let expected = vec![expected];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
@@ -178,45 +472,83 @@ mod tests {
     fn test_integer() {
         let input = String::from("123");
         let expected = vec![Token::Number(123.0)];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
     fn test_negative_integer() {
         let input = String::from("-123");
         let expected = vec![Token::Number(-123.0)];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
     fn test_string() {
         let input = String::from("\"hello\"");
         let expected = vec![Token::String(String::from("hello"))];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
     fn test_unclosed_quotes() {
         let input = String::from("\"unclosed string");
-        assert_eq!(tokenize(input), Err(TokenizeError::UnclosedQuotes));
+        assert_eq!(tokenize(&input), Err(TokenizeError::UnclosedQuotes));
     }
 
     #[test]
     fn test_escape_quotes() {
         let input = String::from(r#""the \" us OK""#);
         let expected = vec![Token::String(String::from(r#"the \" us OK"#))];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
     fn test_float() {
         let input = String::from("123.456");
         let expected = vec![Token::Number(123.456)];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
 
         let input = String::from("-123.456");
         let expected = vec![Token::Number(-123.456)];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_negative_zero() {
+        match tokenize("-0").unwrap()[..] {
+            [Token::Number(number)] => assert!(number == 0.0 && number.is_sign_negative()),
+            ref other => panic!("tokenized as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_large_integer() {
+        let input = String::from("[9223372036854775807, 9223372036854775808, -170141183460469231731687303715884105728]");
+        let expected = vec![
+            Token::LeftBracket,
+            Token::Integer(9223372036854775807),
+            Token::Comma,
+            Token::Integer(9223372036854775808),
+            Token::Comma,
+            Token::Integer(i128::MIN),
+            Token::RightBracket,
+        ];
+        assert_eq!(tokenize(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_exponent() {
+        let input = String::from("[1e3, -2.5E-2, 4e+1]");
+        let expected = vec![
+            Token::LeftBracket,
+            Token::Number(1000.0),
+            Token::Comma,
+            Token::Number(-0.025),
+            Token::Comma,
+            Token::Number(40.0),
+            Token::RightBracket,
+        ];
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
@@ -230,7 +562,7 @@ mod tests {
             Token::Colon,
             Token::Comma,
         ];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
     }
 
     #[test]
@@ -243,6 +575,88 @@ mod tests {
             Token::String(String::from("value")),
             Token::RightBrace,
         ];
-        assert_eq!(tokenize(input).unwrap(), expected);
+        assert_eq!(tokenize(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_non_finite_literals() {
+        let only_infinity = ParseOptions {
+            allow_infinity: true,
+            ..ParseOptions::default()
+        };
+        let (tokens, _) = tokenize_with_spans("[Infinity, -Infinity]", &only_infinity).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftBracket,
+                Token::Number(f64::INFINITY),
+                Token::Comma,
+                Token::Number(f64::NEG_INFINITY),
+                Token::RightBracket,
+            ]
+        );
+        assert_eq!(
+            tokenize_with_spans("NaN", &only_infinity),
+            Err(TokenizeError::CharNotRecognized('N').into())
+        );
+
+        let only_nan = ParseOptions {
+            allow_nan: true,
+            ..ParseOptions::default()
+        };
+        let (tokens, _) = tokenize_with_spans("NaN", &only_nan).unwrap();
+        assert!(matches!(tokens[..], [Token::Number(number)] if number.is_nan()));
+        assert_eq!(
+            tokenize_with_spans("-Infinity", &only_nan),
+            Err(TokenizeError::ParseNumberError("-".parse::<f64>().unwrap_err()).into())
+        );
+
+        assert_eq!(tokenize("Infinity"), Err(TokenizeError::CharNotRecognized('I')));
+    }
+
+    #[test]
+    fn test_tokenize_spanned() {
+        let input = r#" {"k": 12}"#;
+        let spanned = tokenize_spanned(input).unwrap();
+        let expected = [
+            (Token::LeftBrace, Span { start: 1, end: 2 }),
+            (Token::String("k".into()), Span { start: 2, end: 5 }),
+            (Token::Colon, Span { start: 5, end: 6 }),
+            (Token::Number(12.0), Span { start: 7, end: 9 }),
+            (Token::RightBrace, Span { start: 9, end: 10 }),
+        ];
+        assert_eq!(spanned, expected);
+        assert_eq!(&input[spanned[1].1.start..spanned[1].1.end], r#""k""#);
+
+        let spanned = tokenize_spanned("[\"é\", 1e3]").unwrap();
+        assert_eq!(spanned[1].1, Span { start: 1, end: 5 });
+        assert_eq!(spanned[3].1, Span { start: 7, end: 10 });
+    }
+
+    #[test]
+    fn test_is_json_number() {
+        for text in ["0", "-0", "42", "3.14", "-1.5e10", "1E+2", "2e-7", "0.0"] {
+            assert!(is_json_number(text), "{}", text);
+        }
+        for text in ["", "-", "01", "1.", ".5", "+1", "1e", "1e+", "42abc", " 1", "1 ", "0x10", "NaN", "--1"] {
+            assert!(!is_json_number(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("0"), Ok(0.0));
+        assert_eq!(parse_number("-12.5"), Ok(-12.5));
+        assert_eq!(parse_number("1e10"), Ok(1e10));
+        assert_eq!(parse_number("2.5E-3"), Ok(0.0025));
+        assert_eq!(parse_number("99999999999999999999"), Ok(1e20));
+
+        assert_eq!(parse_number("01"), Err(TokenizeError::InvalidNumber("01".into())));
+        assert_eq!(parse_number("1."), Err(TokenizeError::InvalidNumber("1.".into())));
+        assert_eq!(parse_number("-"), Err(TokenizeError::InvalidNumber("-".into())));
+        assert_eq!(parse_number("1x"), Err(TokenizeError::CharNotRecognized('x')));
+        assert_eq!(parse_number("1 "), Err(TokenizeError::CharNotRecognized(' ')));
+        assert_eq!(parse_number("+1"), Err(TokenizeError::CharNotRecognized('+')));
+        assert_eq!(parse_number(""), Err(TokenizeError::UnexpectedEof));
     }
 }
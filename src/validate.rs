@@ -0,0 +1,335 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::parse::{ParseError, TokenParseError};
+use crate::tokenize::{is_json_number, TokenizeError};
+
+type ValidateResult<T> = Result<T, (ParseError, usize)>;
+
+/// Checks that `input` is a single JSON document without building anything, returning the
+/// first error along with its byte offset. The grammar is the one [`parse_strict`](crate::parse_strict)
+/// accepts, except that duplicate keys aren't looked for since that would mean keeping keys.
+///
+/// Valid input is checked without allocating, unless it nests containers more than
+/// 128 levels deep.
+pub fn validate_with_position(input: &str) -> Result<(), (ParseError, usize)> {
+    let mut validator = Validator {
+        input,
+        position: 0,
+        stack: ContainerStack::default(),
+    };
+    validator.validate()
+}
+
+/// The kinds of the open containers, `true` for objects. The innermost 128 are kept in a
+/// bit set so that typical documents don't need to allocate.
+#[derive(Default)]
+struct ContainerStack {
+    bits: u128,
+    len: usize,
+    overflow: Vec<bool>,
+}
+
+impl ContainerStack {
+    fn push(&mut self, is_object: bool) {
+        if self.len >= 128 {
+            self.overflow.push(self.bits >> 127 == 1);
+        }
+        self.bits = (self.bits << 1) | u128::from(is_object);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) {
+        self.bits >>= 1;
+        self.len -= 1;
+        if self.len >= 128 {
+            let outer = self.overflow.pop().expect("the overflow holds every container past 128");
+            self.bits |= u128::from(outer) << 127;
+        }
+    }
+
+    /// Whether the innermost container is an object, or `None` at the top level
+    fn last(&self) -> Option<bool> {
+        (self.len > 0).then_some(self.bits & 1 == 1)
+    }
+}
+
+struct Validator<'a> {
+    input: &'a str,
+    position: usize,
+    stack: ContainerStack,
+}
+
+impl Validator<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    fn error<T>(&self, err: impl Into<ParseError>, position: usize) -> ValidateResult<T> {
+        Err((err.into(), position))
+    }
+
+    fn char_at(&self, position: usize) -> char {
+        self.input[position..].chars().next().unwrap_or('\0')
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn validate(&mut self) -> ValidateResult<()> {
+        loop {
+            while self.value()? {}
+            // close every container that ends here, then either move on to the next value
+            // or finish once back at the top level
+            loop {
+                self.skip_whitespace();
+                let Some(is_object) = self.stack.last() else {
+                    return match self.peek() {
+                        Some(_) => self.error(TokenParseError::TrailingData { position: self.position }, self.position),
+                        None => Ok(()),
+                    };
+                };
+                let (close, expected) = if is_object { (b'}', '}') } else { (b']', ']') };
+                match self.peek() {
+                    None => return self.error(TokenParseError::UnexpectedEof, self.position),
+                    Some(b',') => {
+                        self.position += 1;
+                        if is_object {
+                            self.key()?;
+                        }
+                        break;
+                    }
+                    Some(byte) if byte == close => {
+                        self.position += 1;
+                        self.stack.pop();
+                    }
+                    Some(found @ (b'}' | b']')) => {
+                        let err = TokenParseError::MismatchedBracket {
+                            expected,
+                            found: found as char,
+                            position: self.position,
+                        };
+                        return self.error(err, self.position);
+                    }
+                    Some(_) => return self.error(TokenParseError::ExpectedComma, self.position),
+                }
+            }
+        }
+    }
+
+    /// Reads the next value, or just the opening of a non-empty container and the key of its
+    /// first entry, returning `true` when the container's first value is to be read next
+    fn value(&mut self) -> ValidateResult<bool> {
+        self.skip_whitespace();
+        let start = self.position;
+        match self.peek() {
+            None => self.error(TokenParseError::UnexpectedEof, start),
+            Some(b'{') => {
+                self.position += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                    return Ok(false);
+                }
+                self.stack.push(true);
+                self.key()?;
+                Ok(true)
+            }
+            Some(b'[') => {
+                self.position += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(false);
+                }
+                self.stack.push(false);
+                Ok(true)
+            }
+            Some(b'"') => self.string().map(|_| false),
+            Some(b't') => self.literal("true").map(|_| false),
+            Some(b'f') => self.literal("false").map(|_| false),
+            Some(b'n') => self.literal("null").map(|_| false),
+            Some(b'-' | b'0'..=b'9') => self.number().map(|_| false),
+            Some(b',') => self.error(TokenParseError::UnexpectedComma { position: start }, start),
+            Some(b']' | b'}') => self.error(TokenParseError::ExpectedValue, start),
+            Some(_) => self.error(TokenizeError::CharNotRecognized(self.char_at(start)), start),
+        }
+    }
+
+    /// Reads an object key and its colon, leaving the value to be read next
+    fn key(&mut self) -> ValidateResult<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => self.string()?,
+            Some(b',') => {
+                let position = self.position;
+                return self.error(TokenParseError::UnexpectedComma { position }, position);
+            }
+            None => return self.error(TokenParseError::UnexpectedEof, self.position),
+            Some(_) => return self.error(TokenParseError::ExpectedProperty, self.position),
+        }
+        self.skip_whitespace();
+        if self.peek() != Some(b':') {
+            return self.error(TokenParseError::ExpectedColon, self.position);
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, literal: &str) -> ValidateResult<()> {
+        if !self.input[self.position..].starts_with(literal) {
+            return self.error(TokenizeError::UnfinishedLiteralValue, self.position);
+        }
+        self.position += literal.len();
+        Ok(())
+    }
+
+    fn number(&mut self) -> ValidateResult<()> {
+        let start = self.position;
+        while matches!(self.peek(), Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) {
+            self.position += 1;
+        }
+        let text = &self.input[start..self.position];
+        if !is_json_number(text) {
+            return self.error(TokenizeError::InvalidNumber(text.to_string()), start);
+        }
+        Ok(())
+    }
+
+    fn string(&mut self) -> ValidateResult<()> {
+        let start = self.position;
+        self.position += 1;
+        loop {
+            match self.peek() {
+                None => return self.error(TokenizeError::UnclosedQuotes, start),
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => self.escape()?,
+                Some(byte) if byte < 0x20 => {
+                    return self.error(TokenizeError::ControlCharacter(byte as char), self.position);
+                }
+                Some(_) => self.position += 1,
+            }
+        }
+    }
+
+    /// Reads an escape sequence starting at the backslash
+    fn escape(&mut self) -> ValidateResult<()> {
+        let start = self.position;
+        self.position += 1;
+        match self.peek() {
+            None => self.error(TokenizeError::UnclosedQuotes, start),
+            Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(b'u') => {
+                self.position += 1;
+                let code_point = self.hex()?;
+                let invalid = TokenParseError::InvalidCodePointValue {
+                    value: code_point,
+                    position: start,
+                };
+                match code_point {
+                    // a high surrogate has to be followed by the escape of a low one
+                    0xD800..=0xDBFF => {
+                        if !self.input[self.position..].starts_with("\\u") {
+                            return self.error(invalid, start);
+                        }
+                        self.position += 2;
+                        match self.hex()? {
+                            0xDC00..=0xDFFF => Ok(()),
+                            _ => self.error(invalid, start),
+                        }
+                    }
+                    0xDC00..=0xDFFF => self.error(invalid, start),
+                    _ => Ok(()),
+                }
+            }
+            Some(_) => self.error(TokenParseError::InvalidEscape(self.char_at(self.position)), self.position),
+        }
+    }
+
+    /// Reads the 4 hexadecimal digits of a `\u` escape
+    fn hex(&mut self) -> ValidateResult<u32> {
+        let mut code_point = 0;
+        for _ in 0..4 {
+            let digit = match self.peek() {
+                None | Some(b'"') => return self.error(TokenParseError::UnfinishedEscape, self.position),
+                Some(byte) => (byte as char).to_digit(16),
+            };
+            let Some(digit) = digit else {
+                return self.error(TokenParseError::InvalidHexValue, self.position);
+            };
+            code_point = code_point * 16 + digit;
+            self.position += 1;
+        }
+        Ok(code_point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_alloc::count_allocations;
+
+    #[test]
+    fn test_valid_without_allocating() {
+        let inputs = [
+            r#"{"a": [1, -2.5e3, true, false, null], "b": {"c": "é\n\"", "d": {}}, "e": []}"#,
+            " \"\\uD83D\\uDE00\" ",
+            "[[[[]]], {}]",
+            "0",
+        ];
+        for input in inputs {
+            let (result, allocations) = count_allocations(|| validate_with_position(input));
+            assert_eq!(result, Ok(()), "{}", input);
+            assert_eq!(allocations, 0, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        let deep = format!("{}1{}", "[{\"a\": ".repeat(200), "}]".repeat(200));
+        assert_eq!(validate_with_position(&deep), Ok(()));
+
+        // the outermost containers come back out of the overflow on the way out
+        let wrong = format!("{{\"a\": {}{}}}", "[".repeat(150), "]".repeat(150));
+        assert_eq!(validate_with_position(&wrong), Ok(()));
+        let wrong = format!("{{\"a\": {}{}]", "[".repeat(150), "]".repeat(150));
+        let err = TokenParseError::MismatchedBracket {
+            expected: '}',
+            found: ']',
+            position: wrong.len() - 1,
+        };
+        assert_eq!(validate_with_position(&wrong), Err((err.into(), wrong.len() - 1)));
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let cases: [(&str, ParseError, usize); 12] = [
+            ("[1 2]", TokenParseError::ExpectedComma.into(), 3),
+            (r#"{"a" 1}"#, TokenParseError::ExpectedColon.into(), 5),
+            (r#"{"a": 1,}"#, TokenParseError::ExpectedProperty.into(), 8),
+            ("[1,]", TokenParseError::ExpectedValue.into(), 3),
+            ("[1] 2", TokenParseError::TrailingData { position: 4 }.into(), 4),
+            (r#"{"a": [1}"#, TokenParseError::MismatchedBracket { expected: ']', found: '}', position: 8 }.into(), 8),
+            ("[01]", TokenizeError::InvalidNumber("01".into()).into(), 1),
+            ("[nul]", TokenizeError::UnfinishedLiteralValue.into(), 1),
+            ("\"a\tb\"", TokenizeError::ControlCharacter('\t').into(), 2),
+            (r#"["\q"]"#, TokenParseError::InvalidEscape('q').into(), 3),
+            (r#"["\uD800"]"#, TokenParseError::InvalidCodePointValue { value: 0xD800, position: 2 }.into(), 2),
+            (r#"{"a": [1, "#, TokenParseError::UnexpectedEof.into(), 10),
+        ];
+        for (input, err, position) in cases {
+            assert_eq!(validate_with_position(input), Err((err, position)), "{}", input);
+        }
+        assert_eq!(validate_with_position(""), Err((TokenParseError::UnexpectedEof.into(), 0)));
+        assert_eq!(validate_with_position("[@]"), Err((TokenizeError::CharNotRecognized('@').into(), 1)));
+    }
+}
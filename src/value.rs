@@ -0,0 +1,1169 @@
+use alloc::collections::{btree_map, BTreeMap, VecDeque};
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::tokenize::{inexact_integer, is_json_number};
+use crate::{parse, to_string, ParseError, Value};
+
+/// The kinds of JSON value, without their contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Null,
+    Boolean,
+    /// `Value::Number`, `Value::Integer` and `Value::RawNumber`
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+/// What an array's elements have in common, returned by [`Value::array_element_type`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayElementType {
+    /// Every element is of this kind
+    Homogeneous(ValueKind),
+    /// The elements are of more than one kind
+    Heterogeneous,
+    /// The array has no elements
+    Empty,
+}
+
+/// Relaxations of equality for [`Value::eq_with`]. The default compares like `==`.
+///
+/// Objects always compare regardless of key order, since they don't keep one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EqOptions {
+    /// Compare numbers by value however they are stored, ex. `Value::Integer` against
+    /// `Value::Number` or `Value::RawNumber`
+    pub unify_numbers: bool,
+    /// Treat numbers as equal when they differ by at most this much
+    pub epsilon: Option<f64>,
+    /// Object keys left out of the comparison wherever they appear, ex. timestamps
+    pub ignored_keys: Vec<String>,
+}
+
+/// An object key that isn't a valid JSON string, returned by [`Value::ensure_string_keys`].
+/// Has no variants since `Value::Object`'s key type already rules every such key out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {}
+
+/// A single key of an object, which may or may not be present, returned by [`Value::entry`]
+pub struct Entry<'a> {
+    entry: btree_map::Entry<'a, Arc<str>, Value>,
+}
+
+impl<'a> Entry<'a> {
+    /// Inserts `default` if the key is missing, then returns the key's value
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.entry.or_insert(default)
+    }
+
+    /// Inserts the result of `default` if the key is missing, then returns the key's value
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, default: F) -> &'a mut Value {
+        self.entry.or_insert_with(default)
+    }
+}
+
+impl Value {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Null => ValueKind::Null,
+            Value::Boolean(_) => ValueKind::Boolean,
+            Value::Number(_) | Value::Integer(_) | Value::RawNumber(_) => ValueKind::Number,
+            Value::String(_) => ValueKind::String,
+            Value::Array(_) => ValueKind::Array,
+            Value::Object(_) => ValueKind::Object,
+        }
+    }
+
+    /// Describes whether all elements of an array are of the same kind. Returns `None` for
+    /// non-arrays.
+    pub fn array_element_type(&self) -> Option<ArrayElementType> {
+        let Value::Array(array) = self else {
+            return None;
+        };
+        let Some(first) = array.first() else {
+            return Some(ArrayElementType::Empty);
+        };
+
+        let kind = first.kind();
+        if array.iter().all(|value| value.kind() == kind) {
+            Some(ArrayElementType::Homogeneous(kind))
+        } else {
+            Some(ArrayElementType::Heterogeneous)
+        }
+    }
+
+    /// Looks up `key` in an object. Returns `None` for missing keys and non-objects.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// The keys of an object in map order, or `None` for non-objects
+    pub fn keys(&self) -> Option<Vec<&str>> {
+        match self {
+            Value::Object(map) => Some(map.keys().map(|key| &**key).collect()),
+            _ => None,
+        }
+    }
+
+    /// The values of an object in the same order as [`Value::keys`], or `None` for non-objects
+    pub fn values(&self) -> Option<Vec<&Value>> {
+        match self {
+            Value::Object(map) => Some(map.values().collect()),
+            _ => None,
+        }
+    }
+
+    /// The key and value pairs of an object in map order, or `None` for non-objects
+    pub fn entries(&self) -> Option<Vec<(&str, &Value)>> {
+        match self {
+            Value::Object(map) => Some(map.iter().map(|(key, value)| (&**key, value)).collect()),
+            _ => None,
+        }
+    }
+
+    /// Reorders the keys of every object in the tree lexicographically. Does nothing, as
+    /// objects are `BTreeMap`s that always iterate in sorted key order; kept for code that
+    /// also handles [`OrderedValue`](crate::OrderedValue), where
+    /// [`sort_keys`](crate::OrderedValue::sort_keys) does reorder.
+    pub fn sort_keys(&mut self) {}
+
+    /// Iterates mutably over the elements of an array or the values of an object, in key
+    /// order. Scalars have no children, so nothing is yielded for them.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        let (elements, values) = match self {
+            Value::Array(array) => (Some(array.iter_mut()), None),
+            Value::Object(map) => (None, Some(map.values_mut())),
+            _ => (None, None),
+        };
+        elements.into_iter().flatten().chain(values.into_iter().flatten())
+    }
+
+    /// Looks up an array element, counting from the end for negative indices so that `-1` is
+    /// the last element. Returns `None` when out of range and for non-arrays.
+    pub fn get_index(&self, index: isize) -> Option<&Value> {
+        let Value::Array(array) = self else {
+            return None;
+        };
+        let index = if index < 0 {
+            array.len().checked_sub(index.unsigned_abs())?
+        } else {
+            index as usize
+        };
+        array.get(index)
+    }
+
+    /// Gets the entry for `key` in an object, for in-place get-or-insert
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value isn't an object.
+    pub fn entry(&mut self, key: &str) -> Entry<'_> {
+        match self {
+            Value::Object(map) => Entry {
+                entry: map.entry(Arc::from(key)),
+            },
+            _ => panic!("Value::entry called on a non-object value"),
+        }
+    }
+
+    /// Returns the value as an `i128` if it is an integer, or a number with no fractional part
+    /// that fits in `i128`
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Value::Integer(integer) => Some(*integer),
+            Value::RawNumber(text) => text.parse().ok(),
+            // `i128::MAX as f64` rounds up to 2^127, the first value out of range
+            Value::Number(number)
+                if *number >= i128::MIN as f64 && *number < i128::MAX as f64 && (*number as i128) as f64 == *number =>
+            {
+                Some(*number as i128)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `u128` if it is a non-negative integer, or a non-negative number
+    /// with no fractional part that fits in `u128`
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Integer(integer) => u128::try_from(*integer).ok(),
+            Value::RawNumber(text) => text.parse().ok(),
+            Value::Number(number)
+                if *number >= 0.0 && *number < u128::MAX as f64 && (*number as u128) as f64 == *number =>
+            {
+                Some(*number as u128)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the closest `f64` to any numeric value, whichever way it is stored, and `None`
+    /// for everything else
+    pub fn as_number_lossy(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            Value::Integer(integer) => Some(*integer as f64),
+            Value::RawNumber(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Checks that every object key in the tree is a valid JSON string. Always succeeds: keys
+    /// are `Arc<str>`, so whatever built the object, from parsing to [`Extend`], had to turn
+    /// its keys into text first. Kept for code that wants the check spelled out.
+    pub fn ensure_string_keys(&self) -> Result<(), KeyError> {
+        Ok(())
+    }
+
+    /// Keeps only the object entries for which `f` returns `true`. Does nothing for non-objects.
+    pub fn retain_keys<F: FnMut(&str, &Value) -> bool>(&mut self, mut f: F) {
+        if let Value::Object(map) = self {
+            map.retain(|key, value| f(key, value));
+        }
+    }
+
+    /// Keeps only the array elements for which `f` returns `true`. Does nothing for non-arrays.
+    pub fn retain_elements<F: FnMut(&Value) -> bool>(&mut self, f: F) {
+        if let Value::Array(array) = self {
+            array.retain(f);
+        }
+    }
+
+    /// Splits an object in two: the entries whose key `f` returns `true` for, and the rest.
+    /// Anything but an object is returned as it is in the first slot, with `null` in the second.
+    pub fn partition_object<F: Fn(&str) -> bool>(self, f: F) -> (Value, Value) {
+        match self {
+            Value::Object(map) => {
+                let (matching, rest) = map.into_iter().partition(|(key, _)| f(key));
+                (Value::Object(matching), Value::Object(rest))
+            }
+            other => (other, Value::Null),
+        }
+    }
+
+    /// Removes every object key whose value is `null`, throughout the tree. Nulls in arrays
+    /// are kept since removing them would shift the positions of the elements after them.
+    pub fn filter_nulls(&mut self) {
+        match self {
+            Value::Object(map) => {
+                map.retain(|_, value| *value != Value::Null);
+                map.values_mut().for_each(Value::filter_nulls);
+            }
+            Value::Array(array) => array.iter_mut().for_each(Value::filter_nulls),
+            _ => {}
+        }
+    }
+
+    /// Removes every empty object or array inside this value, throughout the tree. Children
+    /// are pruned before their parents, so containers that only held empty containers go too,
+    /// ex. `{"a": {"b": []}, "c": 1}` becomes `{"c": 1}`. The value itself is kept even if it
+    /// ends up empty.
+    pub fn prune_empty(&mut self) {
+        let is_empty = |value: &Value| match value {
+            Value::Object(map) => map.is_empty(),
+            Value::Array(array) => array.is_empty(),
+            _ => false,
+        };
+        match self {
+            Value::Object(map) => {
+                map.values_mut().for_each(Value::prune_empty);
+                map.retain(|_, value| !is_empty(value));
+            }
+            Value::Array(array) => {
+                array.iter_mut().for_each(Value::prune_empty);
+                array.retain(|value| !is_empty(value));
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns a canonical copy of this value, suitable for hashing or signing once written out
+    /// with [`to_string`](crate::to_string): negative zero becomes zero so that numbers which
+    /// compare equal are written the same way, and objects are always written with sorted keys.
+    /// [`Value::RawNumber`]s become the number parsing their text normally gives, unless it's
+    /// too large for `f64`, ex. `1e400`.
+    pub fn canonicalize(&self) -> Value {
+        match self {
+            Value::Number(number) if *number == 0.0 => Value::Number(0.0),
+            Value::RawNumber(text) => match (inexact_integer(text), text.parse::<f64>()) {
+                (Some(integer), _) => Value::Integer(integer),
+                (None, Ok(number)) if number.is_finite() => Value::Number(number).canonicalize(),
+                _ => self.clone(),
+            },
+            Value::Array(array) => Value::Array(array.iter().map(Value::canonicalize).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), value.canonicalize()))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// A 64-bit FNV-1a hash of the [canonical](Value::canonicalize) JSON text, for caching
+    /// and deduplication. Values that compare equal, whatever order their keys were parsed in,
+    /// hash the same. Not suitable where collisions have to be hard to find on purpose.
+    pub fn checksum(&self) -> u64 {
+        to_string(&self.canonicalize())
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+    }
+
+    /// Fills in whatever `defaults` has that this value is missing. Where both are objects,
+    /// keys already present are kept and merged with their defaults recursively, and missing
+    /// keys are copied over. Anything else present here, including `null`, wins as it is.
+    pub fn apply_defaults(&mut self, defaults: &Value) {
+        if let (Value::Object(map), Value::Object(defaults)) = (self, defaults) {
+            for (key, default) in defaults {
+                match map.get_mut(key) {
+                    Some(value) => value.apply_defaults(default),
+                    None => {
+                        map.insert(key.clone(), default.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renames object keys throughout the tree, using the name `f` returns for each key or
+    /// leaving the key as it is for `None`. When a key is renamed to one already in its object,
+    /// the renamed entry overwrites the existing one.
+    pub fn rename_keys<F: Fn(&str) -> Option<String>>(&mut self, f: F) {
+        self.rename_keys_with(&f);
+    }
+
+    fn rename_keys_with<F: Fn(&str) -> Option<String>>(&mut self, f: &F) {
+        match self {
+            Value::Object(map) => {
+                let mut renamed = Vec::new();
+                for (key, mut value) in core::mem::take(map) {
+                    value.rename_keys_with(f);
+                    match f(&key) {
+                        Some(new_key) => renamed.push((Arc::from(new_key), value)),
+                        None => {
+                            map.insert(key, value);
+                        }
+                    }
+                }
+                map.extend(renamed);
+            }
+            Value::Array(array) => array.iter_mut().for_each(|value| value.rename_keys_with(f)),
+            _ => {}
+        }
+    }
+
+    /// Whether `query` is contained in this value: every key of a query object must be present
+    /// with a value containing the query's value, and every element of a query array must be
+    /// contained in some element of the target array, in any order. Anything else must be equal,
+    /// so `{"a": 1}` is contained in `{"a": 1, "b": 2}` and `[2]` in `[1, 2, 3]`.
+    pub fn contains(&self, query: &Value) -> bool {
+        match (self, query) {
+            (Value::Object(map), Value::Object(query)) => query
+                .iter()
+                .all(|(key, query)| map.get(key).is_some_and(|value| value.contains(query))),
+            (Value::Array(array), Value::Array(query)) => query
+                .iter()
+                .all(|query| array.iter().any(|value| value.contains(query))),
+            (value, query) => value == query,
+        }
+    }
+
+    /// Compares two values with the relaxations in `options`, see [`EqOptions`]
+    pub fn eq_with(&self, other: &Value, options: &EqOptions) -> bool {
+        let numbers_eq = |a: f64, b: f64| match options.epsilon {
+            Some(epsilon) => a == b || (if a > b { a - b } else { b - a }) <= epsilon,
+            None => a == b,
+        };
+        match (self, other) {
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.eq_with(b, options))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                let kept = |key: &Arc<str>| !options.ignored_keys.iter().any(|ignored| **ignored == **key);
+                let mut a = a.iter().filter(|(key, _)| kept(key));
+                let mut b = b.iter().filter(|(key, _)| kept(key));
+                // both are in sorted key order, so matching keys line up
+                loop {
+                    match (a.next(), b.next()) {
+                        (None, None) => return true,
+                        (Some((a_key, a)), Some((b_key, b))) if a_key == b_key && a.eq_with(b, options) => {}
+                        _ => return false,
+                    }
+                }
+            }
+            (Value::Number(a), Value::Number(b)) => numbers_eq(*a, *b),
+            (a, b)
+                if options.unify_numbers
+                    && a.kind() == ValueKind::Number
+                    && b.kind() == ValueKind::Number
+                    && core::mem::discriminant(a) != core::mem::discriminant(b) =>
+            {
+                match (a.as_number_lossy(), b.as_number_lossy()) {
+                    (Some(a), Some(b)) => numbers_eq(a, b),
+                    _ => false,
+                }
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Parses `text` and compares it to this value, for test assertions like
+    /// `assert!(value.matches_text(r#"{"a": 1}"#)?)`. Objects compare regardless of key order.
+    pub fn matches_text(&self, text: &str) -> Result<bool, ParseError> {
+        Ok(parse(text)? == *self)
+    }
+
+    /// Turns strings holding a JSON number, like `"42"` or `"-1.5e3"`, into numbers throughout
+    /// the tree. Any other string is left alone, including ones with surrounding whitespace.
+    pub fn coerce_number(&mut self) {
+        match self {
+            Value::String(string) if is_json_number(string) => {
+                if let Ok(number) = parse(string) {
+                    *self = number;
+                }
+            }
+            Value::Array(array) => array.iter_mut().for_each(Value::coerce_number),
+            Value::Object(map) => map.values_mut().for_each(Value::coerce_number),
+            _ => {}
+        }
+    }
+
+    /// Merges `overlay` into this value position by position: array elements at the same index
+    /// are merged, elements past the end of the base array are appended, and base elements
+    /// past the end of `overlay` are kept. Objects are merged key by key the same way, and
+    /// anything else is replaced by the overlay's value.
+    pub fn merge_arrays_by_index(&mut self, overlay: &Value) {
+        match (self, overlay) {
+            (Value::Array(base), Value::Array(overlay)) => {
+                for (i, overlay) in overlay.iter().enumerate() {
+                    match base.get_mut(i) {
+                        Some(base) => base.merge_arrays_by_index(overlay),
+                        None => base.push(overlay.clone()),
+                    }
+                }
+            }
+            (Value::Object(base), Value::Object(overlay)) => {
+                for (key, overlay) in overlay {
+                    match base.get_mut(key) {
+                        Some(base) => base.merge_arrays_by_index(overlay),
+                        None => {
+                            base.insert(key.clone(), overlay.clone());
+                        }
+                    }
+                }
+            }
+            (base, overlay) => *base = overlay.clone(),
+        }
+    }
+
+    /// Replaces every string value throughout the tree with `f` applied to it, ex. to trim or
+    /// lowercase them. Object keys are left alone, see [`Value::map_strings_and_keys`].
+    pub fn map_strings<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        self.map_strings_with(&mut f, false);
+    }
+
+    /// Like [`Value::map_strings`] but object keys are rewritten by `f` too. When two keys map
+    /// to the same string the entry later in key order wins.
+    pub fn map_strings_and_keys<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        self.map_strings_with(&mut f, true);
+    }
+
+    fn map_strings_with<F: FnMut(&str) -> String>(&mut self, f: &mut F, keys: bool) {
+        match self {
+            Value::String(string) => *string = f(string),
+            Value::Array(array) => array.iter_mut().for_each(|value| value.map_strings_with(f, keys)),
+            Value::Object(map) if keys => {
+                for (key, mut value) in core::mem::take(map) {
+                    value.map_strings_with(f, keys);
+                    map.insert(Arc::from(f(&key)), value);
+                }
+            }
+            Value::Object(map) => map.values_mut().for_each(|value| value.map_strings_with(f, keys)),
+            _ => {}
+        }
+    }
+
+    /// Estimates the memory the value occupies in bytes: the value itself plus every allocation
+    /// it owns, counting string and array capacity rather than length. Object entries are
+    /// counted as their key and value plus the key's allocation, ignoring the map's own node
+    /// overhead and keys shared by interning, so it's a lower bound for objects.
+    pub fn deep_size_bytes(&self) -> usize {
+        core::mem::size_of::<Value>() + self.heap_size_bytes()
+    }
+
+    fn heap_size_bytes(&self) -> usize {
+        match self {
+            Value::String(string) | Value::RawNumber(string) => string.capacity(),
+            Value::Array(array) => {
+                array.capacity() * core::mem::size_of::<Value>()
+                    + array.iter().map(Value::heap_size_bytes).sum::<usize>()
+            }
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| {
+                    // an `Arc` allocation holds the strong and weak counts before the text
+                    core::mem::size_of::<(Arc<str>, Value)>() + 2 * core::mem::size_of::<usize>() + key.len()
+                        + value.heap_size_bytes()
+                })
+                .sum(),
+            _ => 0,
+        }
+    }
+
+    /// Splits an array into arrays of `size` elements, the last one holding whatever is left,
+    /// ex. `[1, 2, 3]` in chunks of 2 is `[[1, 2], [3]]`. Returns `None` for non-arrays and a
+    /// `size` of 0.
+    pub fn chunk_array(&self, size: usize) -> Option<Value> {
+        match self {
+            Value::Array(array) if size > 0 => {
+                Some(Value::Array(array.chunks(size).map(|chunk| Value::Array(chunk.to_vec())).collect()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Replaces the arrays inside an array with their elements, one level deep, ex.
+    /// `[[1, 2], 3, [[4]]]` becomes `[1, 2, 3, [4]]`. Returns `None` for non-arrays.
+    pub fn flatten_one_level(&self) -> Option<Value> {
+        let Value::Array(array) = self else {
+            return None;
+        };
+        let mut flattened = Vec::with_capacity(array.len());
+        for element in array {
+            match element {
+                Value::Array(nested) => flattened.extend(nested.iter().cloned()),
+                value => flattened.push(value.clone()),
+            }
+        }
+        Some(Value::Array(flattened))
+    }
+
+    /// Groups an array of objects by their value at `key`, returning an object from each
+    /// distinct value to the array of elements having it, in their original order. Strings
+    /// group under their text and other values under their compact JSON, so `1` and `"1"`
+    /// share a group. Elements without the key, including any that aren't objects, are put
+    /// in the `"null"` group along with those whose value is `null`. Returns `None` for
+    /// non-arrays.
+    pub fn group_by(&self, key: &str) -> Option<Value> {
+        let Value::Array(array) = self else {
+            return None;
+        };
+        let mut groups = BTreeMap::new();
+        for element in array {
+            let group = key_text(element.get(key).unwrap_or(&Value::Null));
+            match groups.entry(Arc::from(group)).or_insert_with(|| Value::Array(Vec::new())) {
+                Value::Array(members) => members.push(element.clone()),
+                _ => unreachable!("every group is an array"),
+            }
+        }
+        Some(Value::Object(groups))
+    }
+
+    /// Turns an array of objects into an object from each element's value at `key` to the
+    /// element, keyed the same way as [`Value::group_by`]. When several elements share a value
+    /// the last one is kept, as with a duplicate key while parsing. Elements without the key,
+    /// including any that aren't objects, are left out. Returns `None` for non-arrays.
+    pub fn index_by(&self, key: &str) -> Option<Value> {
+        let Value::Array(array) = self else {
+            return None;
+        };
+        let index = array
+            .iter()
+            .filter_map(|element| Some((Arc::from(key_text(element.get(key)?)), element.clone())))
+            .collect();
+        Some(Value::Object(index))
+    }
+
+    /// Returns a copy for logging where containers nested more than `max_depth` levels deep
+    /// are replaced by the string `"…"`. The outermost container is at depth 1, so a
+    /// `max_depth` of 0 collapses everything but a lone scalar.
+    pub fn truncate_depth(&self, max_depth: usize) -> Value {
+        match self {
+            Value::Array(_) | Value::Object(_) if max_depth == 0 => Value::String(String::from("…")),
+            Value::Array(array) => {
+                Value::Array(array.iter().map(|value| value.truncate_depth(max_depth - 1)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), value.truncate_depth(max_depth - 1)))
+                    .collect(),
+            ),
+            scalar => scalar.clone(),
+        }
+    }
+
+    /// Returns a preview of a large document with at most `max_nodes` values in all, chosen
+    /// breadth-first so the top levels are kept first. Each container missing some of its
+    /// children gets a placeholder for them, ex. the string `"…(3 more)"` at the end of an
+    /// array, or under the key `"…"` in an object, and placeholders count towards `max_nodes`.
+    /// The root is always kept, along with its placeholder, even when `max_nodes` is below 2.
+    pub fn sample(&self, max_nodes: usize) -> Value {
+        fn children(value: &Value) -> Vec<&Value> {
+            match value {
+                Value::Array(array) => array.iter().collect(),
+                Value::Object(map) => map.values().collect(),
+                _ => Vec::new(),
+            }
+        }
+        // a kept non-empty container holds on to one node for the placeholder it may need,
+        // handed back if all of its children fit
+        let cost = |value: &Value| if children(value).is_empty() { 1 } else { 2 };
+
+        // visited breadth-first, the children kept of each container are always its first ones
+        let mut kept = BTreeMap::new();
+        let mut budget = max_nodes.saturating_sub(cost(self));
+        let mut queue = VecDeque::from([self]);
+        while let Some(value) = queue.pop_front() {
+            let children = children(value);
+            if children.is_empty() {
+                continue;
+            }
+            let available = budget + 1;
+            let total: usize = children.iter().map(|child| cost(child)).sum();
+            let count = if total <= available {
+                budget = available - total;
+                children.len()
+            } else {
+                budget = available - 1;
+                let mut count = 0;
+                while count < children.len() && cost(children[count]) <= budget {
+                    budget -= cost(children[count]);
+                    count += 1;
+                }
+                count
+            };
+            kept.insert(value as *const Value, count);
+            queue.extend(children.into_iter().take(count));
+        }
+        self.sample_with(&kept)
+    }
+
+    fn sample_with(&self, kept: &BTreeMap<*const Value, usize>) -> Value {
+        let count = kept.get(&(self as *const Value)).copied().unwrap_or(0);
+        let more = |len: usize| Value::String(format!("…({} more)", len - count));
+        match self {
+            Value::Array(array) => {
+                let mut sampled: Vec<Value> = array.iter().take(count).map(|value| value.sample_with(kept)).collect();
+                if count < array.len() {
+                    sampled.push(more(array.len()));
+                }
+                Value::Array(sampled)
+            }
+            Value::Object(map) => {
+                let mut sampled: BTreeMap<Arc<str>, Value> =
+                    map.iter().take(count).map(|(key, value)| (key.clone(), value.sample_with(kept))).collect();
+                if count < map.len() {
+                    // longer runs of `…` for objects that have a key of their own already named so
+                    let mut key = String::from("…");
+                    while map.contains_key(key.as_str()) {
+                        key.push('…');
+                    }
+                    sampled.insert(Arc::from(key), more(map.len()));
+                }
+                Value::Object(sampled)
+            }
+            scalar => scalar.clone(),
+        }
+    }
+}
+
+/// The object key standing for a value: the text of a string, or the compact JSON of anything else
+fn key_text(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => to_string(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retain_keys() {
+        let mut value = parse(r#"{"a": null, "b": 1, "c": null, "d": [null]}"#).unwrap();
+        value.retain_keys(|_, value| *value != Value::Null);
+        assert_eq!(value, parse(r#"{"b": 1, "d": [null]}"#).unwrap());
+
+        let mut array = parse("[null]").unwrap();
+        array.retain_keys(|_, _| false);
+        assert_eq!(array, parse("[null]").unwrap());
+    }
+
+    #[test]
+    fn test_retain_elements() {
+        let mut value = parse("[1, -2, 3, -4.5, 0]").unwrap();
+        value.retain_elements(|value| !matches!(value, Value::Number(n) if *n < 0.0));
+        assert_eq!(value, parse("[1, 3, 0]").unwrap());
+
+        let mut object = parse(r#"{"a": -1}"#).unwrap();
+        object.retain_elements(|_| false);
+        assert_eq!(object, parse(r#"{"a": -1}"#).unwrap());
+    }
+
+    #[test]
+    fn test_partition_object() {
+        let value = parse(r#"{"x_id": 1, "x_name": "a", "other": [true], "y": null}"#).unwrap();
+        let (x, rest) = value.partition_object(|key| key.starts_with("x_"));
+        assert_eq!(x, parse(r#"{"x_id": 1, "x_name": "a"}"#).unwrap());
+        assert_eq!(rest, parse(r#"{"other": [true], "y": null}"#).unwrap());
+
+        let (all, none) = parse(r#"{"a": 1}"#).unwrap().partition_object(|_| true);
+        assert_eq!((all, none), (parse(r#"{"a": 1}"#).unwrap(), parse("{}").unwrap()));
+        assert_eq!(parse("[1]").unwrap().partition_object(|_| true), (parse("[1]").unwrap(), Value::Null));
+    }
+
+    #[test]
+    fn test_filter_nulls() {
+        let input = r#"{"a": null, "b": {"c": null, "d": 1}, "e": [null, {"f": null}], "g": {"h": null}}"#;
+        let mut value = parse(input).unwrap();
+        value.filter_nulls();
+        assert_eq!(value, parse(r#"{"b": {"d": 1}, "e": [null, {}], "g": {}}"#).unwrap());
+
+        let mut null = Value::Null;
+        null.filter_nulls();
+        assert_eq!(null, Value::Null);
+    }
+
+    #[test]
+    fn test_prune_empty() {
+        let input = r#"{"a": null, "b": {"c": null, "d": 1}, "e": [null, {"f": null}, [[]]], "g": {"h": null}}"#;
+        let mut value = parse(input).unwrap();
+        value.filter_nulls();
+        value.prune_empty();
+        assert_eq!(value, parse(r#"{"b": {"d": 1}, "e": [null]}"#).unwrap());
+
+        let mut nested = parse(r#"[{"a": [{}]}, ""]"#).unwrap();
+        nested.prune_empty();
+        assert_eq!(nested, parse(r#"[""]"#).unwrap());
+
+        let mut empty = parse(r#"{"a": {}}"#).unwrap();
+        empty.prune_empty();
+        assert_eq!(empty, parse("{}").unwrap());
+    }
+
+    #[test]
+    fn test_keys_and_values() {
+        let value = parse(r#"{"b": 2, "a": 1, "c": [3]}"#).unwrap();
+        assert_eq!(value.keys(), Some(vec!["a", "b", "c"]));
+        assert_eq!(
+            value.values(),
+            Some(vec![&Value::Number(1.0), &Value::Number(2.0), &Value::Array(vec![Value::Number(3.0)])])
+        );
+
+        let array = parse(r#"["a"]"#).unwrap();
+        assert_eq!(array.keys(), None);
+        assert_eq!(array.values(), None);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut array = parse("[1, 2.5, -3]").unwrap();
+        for value in array.iter_mut() {
+            if let Value::Number(number) = value {
+                *number *= 2.0;
+            }
+        }
+        assert_eq!(array, parse("[2, 5, -6]").unwrap());
+
+        let mut object = parse(r#"{"a": 1, "b": 10}"#).unwrap();
+        object.iter_mut().for_each(|value| *value = Value::Number(value.as_number_lossy().unwrap() + 1.0));
+        assert_eq!(object, parse(r#"{"a": 2, "b": 11}"#).unwrap());
+
+        assert_eq!(Value::String("x".into()).iter_mut().count(), 0);
+    }
+
+    fn snake_case(key: &str) -> Option<String> {
+        if !key.contains(char::is_uppercase) {
+            return None;
+        }
+        let mut snake = String::new();
+        for ch in key.chars() {
+            if ch.is_uppercase() {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        }
+        Some(snake)
+    }
+
+    #[test]
+    fn test_rename_keys() {
+        let mut value = parse(r#"{"userId": 1, "profile": {"firstName": "a", "tags": [{"tagName": "x"}]}}"#).unwrap();
+        value.rename_keys(snake_case);
+        let expected = parse(r#"{"user_id": 1, "profile": {"first_name": "a", "tags": [{"tag_name": "x"}]}}"#);
+        assert_eq!(value, expected.unwrap());
+    }
+
+    #[test]
+    fn test_rename_keys_collision() {
+        // the renamed entry wins, whichever order the keys come in
+        let mut value = parse(r#"{"a_b": "old", "aB": "new", "zY": 1, "z_y": 2}"#).unwrap();
+        value.rename_keys(snake_case);
+        assert_eq!(value, parse(r#"{"a_b": "new", "z_y": 1}"#).unwrap());
+    }
+
+    #[test]
+    fn test_matches_text() -> Result<(), ParseError> {
+        let value = parse(r#"{"a": 1, "b": [true, {"c": null, "d": "x"}]}"#)?;
+        assert!(value.matches_text(r#"{"b": [true, {"d": "x", "c": null}], "a": 1.0}"#)?);
+        assert!(!value.matches_text(r#"{"a": 1, "b": [{"c": null, "d": "x"}, true]}"#)?);
+        assert!(!value.matches_text(r#"{"a": 2, "b": [true, {"c": null, "d": "x"}]}"#)?);
+        assert!(value.matches_text("{").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_objects_iterate_sorted() {
+        let mut value = parse(r#"{"b": 1, "a": {"z": 1, "y": 2}, "c": 3}"#).unwrap();
+        value.entry("0").or_insert(Value::Null);
+        value.extend([(String::from("ab"), Value::Null)]);
+        assert_eq!(value.keys(), Some(vec!["0", "a", "ab", "b", "c"]));
+        assert_eq!(value.get("a").and_then(Value::keys), Some(vec!["y", "z"]));
+
+        let before = value.clone();
+        value.sort_keys();
+        assert_eq!(value, before);
+        let entries = value.get("a").and_then(Value::entries);
+        assert_eq!(entries, Some(vec![("y", &Value::Number(2.0)), ("z", &Value::Number(1.0))]));
+        assert_eq!(Value::Null.entries(), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let target = parse(r#"{"a": 1, "b": 2, "user": {"roles": ["admin", "dev"], "id": 7}, "tags": [{"k": 1}]}"#);
+        let target = target.unwrap();
+        let contains = |query: &str| target.contains(&parse(query).unwrap());
+
+        assert!(contains(r#"{"a": 1}"#));
+        assert!(contains("{}"));
+        assert!(contains(r#"{"user": {"roles": ["dev"]}, "tags": [{"k": 1}]}"#));
+        assert!(!contains(r#"{"a": 2}"#));
+        assert!(!contains(r#"{"c": null}"#));
+        assert!(!contains(r#"{"user": {"roles": ["root"]}}"#));
+        assert!(!contains(r#"{"user": {"id": "7"}}"#));
+        assert!(!Value::Number(1.0).contains(&parse("[1]").unwrap()));
+    }
+
+    #[test]
+    fn test_coerce_number() {
+        let mut value = parse(r#"{"age": "42", "pi": "3.14", "name": "hello", "id": "42abc", "n": 7}"#).unwrap();
+        value.coerce_number();
+        assert_eq!(value, parse(r#"{"age": 42, "pi": 3.14, "name": "hello", "id": "42abc", "n": 7}"#).unwrap());
+
+        let mut value = parse(r#"[["-1e3", " 5", "007"], {"a": ["99999999999999999999"]}, ""]"#).unwrap();
+        value.coerce_number();
+        assert_eq!(value, parse(r#"[[-1e3, " 5", "007"], {"a": [99999999999999999999]}, ""]"#).unwrap());
+    }
+
+    #[test]
+    fn test_merge_arrays_by_index() {
+        let mut base = parse(r#"[{"id": 1, "name": "a", "tags": ["x", "y"]}, {"id": 2}, {"id": 3}]"#).unwrap();
+        let overlay = parse(r#"[{"name": "b", "tags": ["z"], "new": true}]"#).unwrap();
+        base.merge_arrays_by_index(&overlay);
+        let expected = parse(r#"[{"id": 1, "name": "b", "tags": ["z", "y"], "new": true}, {"id": 2}, {"id": 3}]"#);
+        assert_eq!(base, expected.unwrap());
+
+        let mut short = parse("[1, [2]]").unwrap();
+        short.merge_arrays_by_index(&parse(r#"[null, [3, 4], {"a": 5}]"#).unwrap());
+        assert_eq!(short, parse(r#"[null, [3, 4], {"a": 5}]"#).unwrap());
+
+        let mut scalar = Value::Number(1.0);
+        scalar.merge_arrays_by_index(&parse("[1]").unwrap());
+        assert_eq!(scalar, parse("[1]").unwrap());
+    }
+
+    #[test]
+    fn test_map_strings() {
+        let mut value = parse(r#"{"Name": "Ada", "Tags": ["X", {"Y": "Z"}], "n": 1}"#).unwrap();
+        value.map_strings(|s| s.to_lowercase());
+        assert_eq!(value, parse(r#"{"Name": "ada", "Tags": ["x", {"Y": "z"}], "n": 1}"#).unwrap());
+
+        value.map_strings_and_keys(|s| s.to_uppercase());
+        assert_eq!(value, parse(r#"{"NAME": "ADA", "TAGS": ["X", {"Y": "Z"}], "N": 1}"#).unwrap());
+
+        let mut calls = 0;
+        parse(r#"["a", "b", 1]"#).unwrap().map_strings(|s| {
+            calls += 1;
+            s.into()
+        });
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_deep_size_bytes() {
+        let baseline = core::mem::size_of::<Value>();
+        assert_eq!(Value::Null.deep_size_bytes(), baseline);
+        assert_eq!(Value::Array(Vec::new()).deep_size_bytes(), baseline);
+
+        let small = parse(r#"{"a": [1, "x"]}"#).unwrap();
+        let large = parse(r#"{"a": [1, "x"], "b": {"c": "a much longer string value", "d": [null, true]}}"#).unwrap();
+        assert!(small.deep_size_bytes() > baseline);
+        assert!(large.deep_size_bytes() > small.deep_size_bytes());
+        assert_eq!(Value::String("abc".into()).deep_size_bytes(), baseline + 3);
+        assert_eq!(Value::RawNumber("123456".into()).deep_size_bytes(), baseline + 6);
+    }
+
+    #[test]
+    fn test_chunk_array() {
+        let value = parse("[1, 2, 3, 4, 5]").unwrap();
+        assert_eq!(value.chunk_array(2), Some(parse("[[1, 2], [3, 4], [5]]").unwrap()));
+        assert_eq!(value.chunk_array(5), Some(parse("[[1, 2, 3, 4, 5]]").unwrap()));
+        assert_eq!(value.chunk_array(0), None);
+        assert_eq!(parse("[]").unwrap().chunk_array(3), Some(parse("[]").unwrap()));
+        assert_eq!(parse("{}").unwrap().chunk_array(1), None);
+    }
+
+    #[test]
+    fn test_flatten_one_level() {
+        let value = parse("[[1, 2], [3], [4]]").unwrap();
+        assert_eq!(value.flatten_one_level(), Some(parse("[1, 2, 3, 4]").unwrap()));
+        let value = parse(r#"[[], 1, [[2]], {"a": [3]}]"#).unwrap();
+        assert_eq!(value.flatten_one_level(), Some(parse(r#"[1, [2], {"a": [3]}]"#).unwrap()));
+        assert_eq!(Value::Null.flatten_one_level(), None);
+    }
+
+    #[test]
+    fn test_ensure_string_keys() {
+        assert_eq!(parse(r#"{"a": {"": [{"é": 1}]}, "1": null}"#).unwrap().ensure_string_keys(), Ok(()));
+
+        let mut built = Value::Object(BTreeMap::new());
+        built.extend((0..3).map(|n| (format!("{}", n), Value::Number(n as f64))));
+        assert_eq!(built.ensure_string_keys(), Ok(()));
+        assert_eq!(Value::Null.ensure_string_keys(), Ok(()));
+    }
+
+    #[test]
+    fn test_group_by() {
+        let value = parse(
+            r#"[{"category": "fruit", "name": "apple"}, {"category": "veg", "name": "leek"},
+                {"category": "fruit", "name": "pear"}, {"name": "salt"}, {"category": 1}, 2]"#,
+        )
+        .unwrap();
+        let expected = parse(
+            r#"{"fruit": [{"category": "fruit", "name": "apple"}, {"category": "fruit", "name": "pear"}],
+                "veg": [{"category": "veg", "name": "leek"}], "1": [{"category": 1}], "null": [{"name": "salt"}, 2]}"#,
+        )
+        .unwrap();
+        assert_eq!(value.group_by("category"), Some(expected));
+        assert_eq!(parse("[]").unwrap().group_by("category"), Some(parse("{}").unwrap()));
+        assert_eq!(parse(r#"{"category": 1}"#).unwrap().group_by("category"), None);
+    }
+
+    #[test]
+    fn test_index_by() {
+        let value = parse(r#"[{"id": "a", "n": 1}, {"id": "b", "n": 2}, {"id": 3}, {"n": 4}, "c"]"#).unwrap();
+        let expected = parse(r#"{"a": {"id": "a", "n": 1}, "b": {"id": "b", "n": 2}, "3": {"id": 3}}"#).unwrap();
+        assert_eq!(value.index_by("id"), Some(expected));
+
+        let duplicates = parse(r#"[{"id": "a", "n": 1}, {"id": "a", "n": 2}]"#).unwrap();
+        assert_eq!(duplicates.index_by("id"), Some(parse(r#"{"a": {"id": "a", "n": 2}}"#).unwrap()));
+        assert_eq!(Value::Null.index_by("id"), None);
+    }
+
+    #[test]
+    fn test_eq_with() {
+        let a = parse(r#"{"id": 1, "score": 0.30000000000000004, "at": "10:00", "tags": [1.0]}"#).unwrap();
+        let b = parse(r#"{"id": 1, "score": 0.3, "at": "10:01", "tags": [1]}"#).unwrap();
+        assert!(!a.eq_with(&b, &EqOptions::default()));
+
+        let options = EqOptions {
+            epsilon: Some(1e-9),
+            ignored_keys: vec!["at".into()],
+            ..EqOptions::default()
+        };
+        assert!(a.eq_with(&b, &options));
+        assert!(!a.eq_with(&parse(r#"{"id": 1, "score": 0.31, "tags": [1]}"#).unwrap(), &options));
+        assert!(!a.eq_with(&parse(r#"{"id": 1, "score": 0.3, "tags": [1], "x": 1}"#).unwrap(), &options));
+        assert!(a.eq_with(&parse(r#"{"id": 1, "score": 0.3, "tags": [1]}"#).unwrap(), &options));
+
+        let huge = Value::Integer(1 << 70);
+        let options = EqOptions {
+            unify_numbers: true,
+            ..EqOptions::default()
+        };
+        assert!(!huge.eq_with(&Value::Number((1u128 << 70) as f64), &EqOptions::default()));
+        assert!(huge.eq_with(&Value::Number((1u128 << 70) as f64), &options));
+        assert!(Value::RawNumber("2.50".into()).eq_with(&Value::Number(2.5), &options));
+        assert!(!Value::String("1".into()).eq_with(&Value::Number(1.0), &options));
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let a = parse(r#"{"b": [1, {"y": -0, "x": 2}], "a": "s"}"#).unwrap();
+        let b = parse(r#"{"a": "s", "b": [1, {"x": 2, "y": 0}]}"#).unwrap();
+
+        assert_eq!(a.canonicalize(), b.canonicalize());
+        assert_eq!(to_string(&a.canonicalize()), to_string(&b.canonicalize()));
+        assert_eq!(to_string(&a.canonicalize()), r#"{"a":"s","b":[1,{"x":2,"y":0}]}"#);
+        assert_ne!(a.canonicalize(), parse(r#"{"a": "s", "b": [1]}"#).unwrap().canonicalize());
+
+        let raw = ["1.0", "-0", "12345678901234567890123", "1e400"].map(|text| Value::RawNumber(text.into()));
+        let raw = Value::Array(raw.into());
+        let numbers = parse("[1, 0, 12345678901234567890123]").unwrap();
+        let Value::Array(mut canonical) = raw.canonicalize() else { unreachable!() };
+        assert_eq!(canonical.pop(), Some(Value::RawNumber("1e400".into())));
+        assert_eq!(Value::Array(canonical), numbers.canonicalize());
+        assert_eq!(Value::RawNumber("1.0".into()).canonicalize(), Value::Number(1.0).canonicalize());
+    }
+
+    #[test]
+    fn test_checksum() {
+        let a = parse(r#"{"b": [1, {"y": null, "x": -0}], "a": "text"}"#).unwrap();
+        let b = parse(r#"{ "a" : "text", "b" : [1.0, {"x": 0, "y": null}] }"#).unwrap();
+        assert_eq!(a.checksum(), b.checksum());
+        assert_ne!(a.checksum(), parse(r#"{"a": "text", "b": [2, {"x": 0, "y": null}]}"#).unwrap().checksum());
+        assert_ne!(parse("[1, 2]").unwrap().checksum(), parse("[2, 1]").unwrap().checksum());
+    }
+
+    #[test]
+    fn test_apply_defaults() {
+        let defaults = parse(
+            r#"{"port": 80, "tls": {"enabled": false, "cert": "a.pem"}, "hosts": ["localhost"], "log": {"level": "info"}}"#,
+        )
+        .unwrap();
+        let mut config = parse(r#"{"port": 8080, "tls": {"enabled": true}, "hosts": [], "log": null}"#).unwrap();
+        config.apply_defaults(&defaults);
+
+        let expected = parse(
+            r#"{"port": 8080, "tls": {"enabled": true, "cert": "a.pem"}, "hosts": [], "log": null}"#,
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+
+        let mut empty = parse("{}").unwrap();
+        empty.apply_defaults(&defaults);
+        assert_eq!(empty, defaults);
+
+        let mut scalar = Value::Number(1.0);
+        scalar.apply_defaults(&defaults);
+        assert_eq!(scalar, Value::Number(1.0));
+    }
+
+    #[test]
+    fn test_truncate_depth() {
+        let value = parse(r#"{"a": {"b": {"c": {"d": {"e": 1}}}, "f": [2, []]}, "g": "x"}"#).unwrap();
+        assert_eq!(
+            value.truncate_depth(2),
+            parse(r#"{"a": {"b": "…", "f": "…"}, "g": "x"}"#).unwrap()
+        );
+        assert_eq!(value.truncate_depth(5), value);
+        assert_eq!(value.truncate_depth(0), Value::String("…".into()));
+        assert_eq!(Value::Null.truncate_depth(0), Value::Null);
+    }
+
+    #[test]
+    fn test_sample() {
+        let value = parse(r#"{"a": [1, 2, 3, 4], "b": {"c": {"d": [5]}}, "e": 6}"#).unwrap();
+        assert_eq!(
+            value.sample(8),
+            parse(r#"{"a": [1, 2, "…(2 more)"], "b": {"…": "…(1 more)"}, "e": 6}"#).unwrap()
+        );
+        assert_eq!(
+            value.sample(6),
+            parse(r#"{"a": ["…(4 more)"], "b": {"…": "…(1 more)"}, "e": 6}"#).unwrap()
+        );
+        assert_eq!(value.sample(1), parse(r#"{"…": "…(3 more)"}"#).unwrap());
+        assert_eq!(value.sample(100), value);
+        assert_eq!(Value::Null.sample(0), Value::Null);
+
+        let wide = Value::Array((0..1000).map(|i| parse(&format!("[{}, [{}]]", i, i)).unwrap()).collect());
+        for max_nodes in [2, 3, 10, 50, 51, 500] {
+            assert!(wide.sample(max_nodes).walk().count() <= max_nodes, "{}", max_nodes);
+        }
+        assert_eq!(wide.sample(50).walk().count(), 50);
+
+        // a real key named `…` is kept apart from the placeholder
+        let dots = parse(r#"{"…": 1, "x": 2, "y": 3}"#).unwrap();
+        assert_eq!(dots.sample(3), parse(r#"{"x": 2, "……": "…(2 more)"}"#).unwrap());
+        assert_eq!(dots.sample(4), dots);
+    }
+
+    #[test]
+    fn test_large_integers() {
+        for text in ["9223372036854775808", "-9223372036854775809", "170141183460469231731687303715884105727"] {
+            let value = parse(text).unwrap();
+            assert_eq!(value.as_i128(), Some(text.parse::<i128>().unwrap()));
+            assert_eq!(to_string(&value), text);
+        }
+
+        // within `i64` but past what `f64` holds exactly, ex. nanosecond timestamps
+        let timestamp = parse(r#"{"ns": 1700000000123456789}"#).unwrap();
+        assert_eq!(timestamp.get("ns"), Some(&Value::Integer(1700000000123456789)));
+        assert_eq!(to_string(&timestamp), r#"{"ns":1700000000123456789}"#);
+        assert_eq!(parse("1700000000123456789"), Ok(Value::Integer(1700000000123456789)));
+        assert_eq!(parse("9007199254740992"), Ok(Value::Number(9007199254740992.0)));
+        assert_eq!(to_string(&Value::Number(1700000000123456789.0)), "1.7000000001234568e18");
+
+        assert_eq!(parse("18446744073709551616").unwrap().as_u128(), Some(1 << 64));
+        assert_eq!(parse("-18446744073709551616").unwrap().as_u128(), None);
+        assert_eq!(parse("42").unwrap().as_i128(), Some(42));
+        assert_eq!(parse("-42").unwrap().as_u128(), None);
+        assert_eq!(parse("4.5").unwrap().as_i128(), None);
+        assert_eq!(parse("\"1\"").unwrap().as_i128(), None);
+    }
+
+    #[test]
+    fn test_as_number_lossy() {
+        assert_eq!(parse("42").unwrap().as_number_lossy(), Some(42.0));
+        assert_eq!(parse("-2.5").unwrap().as_number_lossy(), Some(-2.5));
+        assert_eq!(parse("18446744073709551617").unwrap().as_number_lossy(), Some(18446744073709551616.0));
+        assert_eq!(parse("\"42\"").unwrap().as_number_lossy(), None);
+        assert_eq!(parse("[42]").unwrap().as_number_lossy(), None);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut value = parse(r#"{"other": 1}"#).unwrap();
+        for n in [1.0, 2.0] {
+            match value.entry("list").or_insert(Value::Array(vec![])) {
+                Value::Array(list) => list.push(Value::Number(n)),
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        *value.entry("other").or_insert_with(|| Value::Null) = Value::Boolean(true);
+
+        assert_eq!(value, parse(r#"{"other": true, "list": [1, 2]}"#).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_entry_non_object() {
+        Value::Array(vec![]).entry("key");
+    }
+
+    #[test]
+    fn test_array_element_type() {
+        let element_type = |input| parse(input).unwrap().array_element_type();
+
+        assert_eq!(
+            element_type("[1, 2.5, 18446744073709551616]"),
+            Some(ArrayElementType::Homogeneous(ValueKind::Number))
+        );
+        assert_eq!(element_type(r#"[1, "2", null]"#), Some(ArrayElementType::Heterogeneous));
+        assert_eq!(element_type("[]"), Some(ArrayElementType::Empty));
+        assert_eq!(element_type("[[1], []]"), Some(ArrayElementType::Homogeneous(ValueKind::Array)));
+        assert_eq!(element_type("{}"), None);
+    }
+
+    #[test]
+    fn test_get_index() {
+        let value = parse(r#"["a", "b", "c"]"#).unwrap();
+        let string = |s: &str| Some(Value::String(s.into()));
+
+        assert_eq!(value.get_index(0).cloned(), string("a"));
+        assert_eq!(value.get_index(-1).cloned(), string("c"));
+        assert_eq!(value.get_index(-3).cloned(), string("a"));
+        assert_eq!(value.get_index(3), None);
+        assert_eq!(value.get_index(-4), None);
+        assert_eq!(value.get_index(isize::MIN), None);
+        assert_eq!(parse(r#"{"0": 1}"#).unwrap().get_index(0), None);
+    }
+}